@@ -0,0 +1,75 @@
+//! Compares the throughput of `Json` and `BufferedJson` when serializing a
+//! million messages of 100 bytes each, and that of `BufferedJson` with and
+//! without a preallocated capacity for messages of 4 KB.
+//!
+//! Run with `cargo run --release --example buffered_throughput`.
+
+use std::{pin::Pin, time::Instant};
+
+use serde::Serialize;
+use tokio_serde::Serializer;
+use tokio_serde_codecs::{BufferedJson, Json};
+
+const MESSAGES: usize = 1_000_000;
+const LARGE_MESSAGES: usize = 100_000;
+
+#[derive(Serialize)]
+struct Message {
+    id: u64,
+    kind: &'static str,
+    payload: String,
+}
+
+/// Serializes `messages` with `codec`, and prints the number of messages and
+/// bytes serialized per second.
+fn run<C>(name: &str, mut codec: C, messages: &[Message])
+where
+    C: Serializer<Message> + Unpin,
+    C::Error: std::fmt::Debug,
+{
+    let start = Instant::now();
+    let mut bytes = 0;
+
+    for message in messages {
+        // The frame is dropped right away, as if it had been written to the
+        // socket
+        bytes += Pin::new(&mut codec).serialize(message).unwrap().len();
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{:>20}: {:.0} messages/s, {:.1} MB/s",
+        name,
+        messages.len() as f64 / elapsed,
+        bytes as f64 / elapsed / 1_000_000.0,
+    );
+}
+
+/// Creates `count` messages with a payload of `len` bytes.
+fn messages(count: usize, len: usize) -> Vec<Message> {
+    let messages: Vec<_> = (0..count as u64)
+        .map(|id| Message {
+            id,
+            kind: "event",
+            payload: "x".repeat(len),
+        })
+        .collect();
+
+    let len = serde_json::to_vec(&messages[0]).unwrap().len();
+    println!("{} messages of about {} bytes", count, len);
+    messages
+}
+
+pub fn main() {
+    let small = messages(MESSAGES, 64);
+    run("Json", Json::<(), _>::new(), &small);
+    run("BufferedJson", BufferedJson::new(), &small);
+
+    let large = messages(LARGE_MESSAGES, 4 * 1024 - 36);
+    run("BufferedJson", BufferedJson::new(), &large);
+    run(
+        "with_capacity(4400)",
+        BufferedJson::with_capacity(4400),
+        &large,
+    );
+}
@@ -4,7 +4,7 @@ use tokio::{
     codec::{FramedWrite, LengthDelimitedCodec},
     net::TcpStream,
 };
-use tokio_serde_json::WriteJson;
+use tokio_serde_codecs::{Json, WriteJson};
 
 #[tokio::main]
 pub async fn main() {
@@ -15,7 +15,7 @@ pub async fn main() {
     let length_delimited = FramedWrite::new(socket, LengthDelimitedCodec::new());
 
     // Serialize frames with JSON
-    let mut serialized = WriteJson::new(length_delimited);
+    let mut serialized = WriteJson::new(length_delimited, Json::new());
 
     // Send the value
     serialized
@@ -0,0 +1,29 @@
+use futures::prelude::*;
+
+use serde_json::json;
+
+use tokio::net::TcpStream;
+
+use tokio_serde_codecs::write_json_from_io;
+
+#[tokio::main]
+async fn main() {
+    // Bind a server socket
+    let socket = TcpStream::connect("127.0.0.1:17653").await.unwrap();
+
+    // Delimit frames using a length header and serialize them with JSON
+    let mut serialized = write_json_from_io(socket);
+
+    // Send the value
+    serialized
+        .send(json!({
+            "name": "John Doe",
+            "age": 43,
+            "phones": [
+                "+44 1234567",
+                "+44 2345678"
+            ]
+        }))
+        .await
+        .unwrap()
+}
@@ -0,0 +1,48 @@
+use futures::prelude::*;
+use serde_json::{json, Value};
+use tokio::{
+    codec::{Framed, LengthDelimitedCodec},
+    net::{TcpListener, TcpStream},
+};
+use tokio_serde_codecs::{Json, ReadJson, WriteJson};
+
+#[tokio::main]
+pub async fn main() {
+    // Bind a server socket
+    let listener = TcpListener::bind("127.0.0.1:17654").await.unwrap();
+
+    // Echo every message back to the peer it came from
+    tokio::spawn(async move {
+        let mut s = listener.incoming();
+
+        while let Some(socket) = s.try_next().await.unwrap() {
+            // Delimit frames in both directions using a length header
+            let length_delimited = Framed::new(socket, LengthDelimitedCodec::new());
+
+            // Read and write the same message type over one transport
+            let transport = WriteJson::new(
+                ReadJson::<_, Value>::new(length_delimited, Json::new()),
+                Json::new(),
+            );
+
+            tokio::spawn(async move {
+                let (sink, stream) = transport.split();
+                stream.forward(sink).await.unwrap();
+            });
+        }
+    });
+
+    let socket = TcpStream::connect("127.0.0.1:17654").await.unwrap();
+    let length_delimited = Framed::new(socket, LengthDelimitedCodec::new());
+    let mut transport = WriteJson::new(
+        ReadJson::<_, Value>::new(length_delimited, Json::new()),
+        Json::new(),
+    );
+
+    for i in 0..3 {
+        transport.send(json!({ "ping": i })).await.unwrap();
+
+        let pong = transport.try_next().await.unwrap();
+        println!("GOT: {:?}", pong);
+    }
+}
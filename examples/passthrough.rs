@@ -0,0 +1,93 @@
+//! Compares the allocations made by forwarding a large message as a
+//! `serde_json::Value` and as a `RawValue`.
+//!
+//! Run with `cargo run --release --example passthrough --features raw-value`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use bytes::{Bytes, BytesMut};
+use serde_json::{json, value::RawValue, Value};
+use tokio_serde::{Deserializer, Serializer};
+use tokio_serde_codecs::{Json, RawJson};
+
+const ROUNDS: usize = 100;
+
+/// Allocator counting the allocations made through it.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Returns the number of allocations made by `f`, on average over `ROUNDS`
+/// calls.
+fn allocations(mut f: impl FnMut()) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..ROUNDS {
+        f();
+    }
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / ROUNDS as f64
+}
+
+/// Forwards `frame` through `codec`, printing the allocations made by
+/// deserializing and serializing it.
+fn run<T, C>(name: &str, mut codec: C, frame: &BytesMut)
+where
+    C: Deserializer<T> + Serializer<T> + Unpin,
+    <C as Deserializer<T>>::Error: std::fmt::Debug,
+    <C as Serializer<T>>::Error: std::fmt::Debug,
+{
+    let value = Pin::new(&mut codec).deserialize(frame).unwrap();
+    let deserialize = allocations(|| {
+        Pin::new(&mut codec).deserialize(frame).unwrap();
+    });
+    let serialize = allocations(|| {
+        let out: Bytes = Pin::new(&mut codec).serialize(&value).unwrap();
+        assert_eq!(out.len(), frame.len());
+    });
+
+    println!(
+        "{:>12}: {:>8.1} allocations to deserialize, {:>4.1} to serialize",
+        name, deserialize, serialize,
+    );
+}
+
+pub fn main() {
+    // About 50 KB of JSON
+    let records: Vec<Value> = (0..700)
+        .map(|id| {
+            json!({
+                "id": id,
+                "name": format!("user-{:05}", id),
+                "tags": ["alpha", "beta", "gamma"],
+                "score": 0.5,
+            })
+        })
+        .collect();
+    let frame = BytesMut::from(serde_json::to_vec(&records).unwrap());
+    println!("frame of {} bytes", frame.len());
+
+    run::<Value, _>("Json<Value>", Json::<Value>::new(), &frame);
+    run::<Box<RawValue>, _>("RawJson", RawJson::new(), &frame);
+}
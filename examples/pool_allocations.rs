@@ -0,0 +1,100 @@
+//! Compares the number of allocations made by `Json` and `PooledJson` while
+//! many connections serialize messages concurrently.
+//!
+//! Run with `cargo run --release --example pool_allocations`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Instant,
+};
+
+use serde_json::{json, Value};
+use tokio_serde::Serializer;
+use tokio_serde_codecs::{BufferPool, Json, PooledJson};
+
+const CONNECTIONS: usize = 8;
+const MESSAGES: usize = 100_000;
+
+/// Allocator counting the allocations made through it.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Serializes `MESSAGES` messages on each of `CONNECTIONS` threads, using a
+/// serializer per connection created by `codec`, and prints the number of
+/// allocations made per message.
+fn run<C, F>(name: &str, codec: F)
+where
+    C: Serializer<Value> + Unpin + Send + 'static,
+    C::Error: std::fmt::Debug,
+    F: Fn() -> C,
+{
+    let message = json!({
+        "id": 12345,
+        "name": "John Doe",
+        "phones": ["+44 1234567", "+44 2345678"],
+    });
+
+    let codecs: Vec<C> = (0..CONNECTIONS).map(|_| codec()).collect();
+
+    let start = Instant::now();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    let threads: Vec<_> = codecs
+        .into_iter()
+        .map(|mut codec| {
+            let message = message.clone();
+            thread::spawn(move || {
+                for _ in 0..MESSAGES {
+                    // The frame is dropped right away, as if it had been
+                    // written to the socket
+                    Pin::new(&mut codec).serialize(&message).unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    let frames = CONNECTIONS * MESSAGES;
+    println!(
+        "{:>10}: {:.3} allocations per frame, {:?} in total",
+        name,
+        allocations as f64 / frames as f64,
+        start.elapsed(),
+    );
+}
+
+pub fn main() {
+    run("Json", Json::<Value>::new);
+
+    let pool = Arc::new(BufferPool::new(CONNECTIONS, 256));
+    run("PooledJson", || PooledJson::<Value>::new(pool.clone()));
+}
@@ -0,0 +1,27 @@
+use futures::prelude::*;
+use serde::Deserialize;
+use tokio::fs::File;
+use tokio_serde_codecs::JsonStream;
+
+#[derive(Debug, Deserialize)]
+struct Person {
+    name: String,
+    age: u32,
+}
+
+#[tokio::main]
+pub async fn main() {
+    // A file of concatenated objects, e.g. `{"name":"John Doe","age":43}`
+    // once per line
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "people.json".to_owned());
+    let file = File::open(path).await.unwrap();
+
+    // Deserialize the objects one after another, without any framing
+    let people: Vec<Person> = JsonStream::new(file).try_collect().await.unwrap();
+
+    for person in &people {
+        println!("{} is {} years old", person.name, person.age);
+    }
+}
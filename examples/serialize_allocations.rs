@@ -0,0 +1,88 @@
+//! Compares the number of allocations made by `Json`, which serializes
+//! straight into the buffer of the frame, with serializing into a `Vec`
+//! first and converting it into `Bytes`.
+//!
+//! Both start out with room for 128 bytes, so each of the messages here
+//! takes a single allocation, whereas an empty `BytesMut` would have to grow
+//! along the way.
+//!
+//! Run with `cargo run --release --example serialize_allocations`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use bytes::Bytes;
+use serde_json::{json, Value};
+use tokio_serde::Serializer;
+use tokio_serde_codecs::Json;
+
+const MESSAGES: usize = 1_000_000;
+
+/// Allocator counting the allocations made through it.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Turns `MESSAGES` messages into frames with `serialize`, and prints the
+/// number of allocations made per frame.
+fn run<F>(name: &str, mut serialize: F)
+where
+    F: FnMut(&Value) -> Bytes,
+{
+    let message = json!({
+        "id": 12345,
+        "name": "John Doe",
+        "phones": ["+44 1234567", "+44 2345678"],
+    });
+
+    let start = Instant::now();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    for _ in 0..MESSAGES {
+        // The frame is dropped right away, as if it had been written to the
+        // socket
+        serialize(&message);
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:>8}: {:.3} allocations per frame, {:?} in total",
+        name,
+        allocations as f64 / MESSAGES as f64,
+        start.elapsed(),
+    );
+}
+
+pub fn main() {
+    run("to_vec", |message| {
+        serde_json::to_vec(message).unwrap().into()
+    });
+
+    let mut json = Json::<Value>::new();
+    run("Json", |message| {
+        Pin::new(&mut json).serialize(message).unwrap()
+    });
+}
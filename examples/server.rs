@@ -4,7 +4,7 @@ use tokio::{
     codec::{FramedRead, LengthDelimitedCodec},
     net::TcpListener,
 };
-use tokio_serde_json::ReadJson;
+use tokio_serde_codecs::{Json, ReadJson};
 
 #[tokio::main]
 pub async fn main() {
@@ -20,7 +20,7 @@ pub async fn main() {
         let length_delimited = FramedRead::new(socket, LengthDelimitedCodec::new());
 
         // Deserialize frames
-        let mut deserialized = ReadJson::<_, Value>::new(length_delimited);
+        let mut deserialized = ReadJson::<_, Value>::new(length_delimited, Json::new());
 
         // Spawn a task that prints all received messages to STDOUT
         tokio::spawn(async move {
@@ -0,0 +1,59 @@
+//! Compares parsing a 10 KB document with the simd-json based deserializer
+//! of `Json` and with serde_json.
+//!
+//! Run with `cargo run --release --features simd --example simd_parse`.
+
+use std::{pin::Pin, time::Instant};
+
+use bytes::BytesMut;
+use serde_json::{json, Value};
+use tokio_serde::Deserializer;
+use tokio_serde_codecs::Json;
+
+const ITERATIONS: usize = 20_000;
+
+/// Parses `frame` `ITERATIONS` times with `parse`, and prints the time taken
+/// per document and the resulting throughput.
+fn run<F>(name: &str, frame: &BytesMut, mut parse: F)
+where
+    F: FnMut(&BytesMut) -> Value,
+{
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        parse(frame);
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:>10}: {:?} per document, {:.1} MB/s",
+        name,
+        elapsed / ITERATIONS as u32,
+        (frame.len() * ITERATIONS) as f64 / elapsed.as_secs_f64() / 1_000_000.0,
+    );
+}
+
+pub fn main() {
+    // A document of about 10 KB, mixing the kinds of values
+    let users: Vec<Value> = (0..80)
+        .map(|id| {
+            json!({
+                "id": id,
+                "name": format!("user {}", id),
+                "active": id % 3 != 0,
+                "score": id as f64 * 1.25,
+                "tags": ["admin", "staff", "remote"],
+                "address": { "city": "Oslo", "zip": "0150" },
+            })
+        })
+        .collect();
+    let frame = BytesMut::from(serde_json::to_vec(&json!({ "users": users })).unwrap());
+    println!("document of {} bytes", frame.len());
+
+    let mut json = Json::<Value>::new();
+    run("simd-json", &frame, |frame| {
+        Pin::new(&mut json).deserialize(frame).unwrap()
+    });
+    run("serde_json", &frame, |frame| {
+        serde_json::from_slice(frame).unwrap()
+    });
+}
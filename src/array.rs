@@ -0,0 +1,160 @@
+use bytes::{Bytes, BytesMut};
+use serde::Serialize;
+
+use std::fmt;
+
+use crate::{CodecError, Json};
+
+/// Encoder writing a JSON array one element at a time.
+///
+/// A large result set can be sent as a single JSON array without holding
+/// all of it in memory: [`begin`] returns the opening bracket, [`push`] each
+/// element with the comma separating it from the previous one, and
+/// [`finish`] the closing bracket. The chunks, written to the transport in
+/// order, add up to a valid JSON array, which may be empty.
+///
+/// The elements are serialized with the options of a [`Json`], given to
+/// [`with_json`]. The chunks are not JSON values on their own, so they are
+/// meant for a transport without framing, such as a plain byte stream or
+/// the body of an HTTP response, rather than for one frame each.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use tokio_serde_codecs::JsonArrayWriter;
+///
+/// fn stream(items: &[u32]) -> Vec<u32> {
+///     let mut writer = JsonArrayWriter::<u32>::new();
+///     let mut body = BytesMut::new();
+///
+///     body.extend_from_slice(&writer.begin());
+///     for item in items {
+///         body.extend_from_slice(&writer.push(item).unwrap());
+///     }
+///     body.extend_from_slice(&writer.finish());
+///
+///     serde_json::from_slice(&body).unwrap()
+/// }
+///
+/// assert!(stream(&[]).is_empty());
+/// assert_eq!(stream(&[1]), [1]);
+/// assert_eq!(stream(&[1, 2, 3]), [1, 2, 3]);
+/// ```
+///
+/// [`begin`]: #method.begin
+/// [`push`]: #method.push
+/// [`finish`]: #method.finish
+/// [`with_json`]: #method.with_json
+/// [`Json`]: struct.Json.html
+pub struct JsonArrayWriter<T> {
+    json: Json<T>,
+    state: State,
+}
+
+/// What has been written of the array so far.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Nothing, not even the opening bracket.
+    Initial,
+    /// The opening bracket.
+    Empty,
+    /// The opening bracket and at least one element.
+    Elements,
+}
+
+impl<T> JsonArrayWriter<T> {
+    /// Creates a new writer of a JSON array.
+    pub fn new() -> Self {
+        Self::with_json(Json::new())
+    }
+
+    /// Creates a new writer of a JSON array serializing its elements with
+    /// `json`.
+    pub fn with_json(json: Json<T>) -> Self {
+        JsonArrayWriter {
+            json,
+            state: State::Initial,
+        }
+    }
+
+    /// Returns the opening bracket of the array.
+    ///
+    /// The chunk is empty if the array has already been started, which
+    /// [`push`] does as well if needed.
+    ///
+    /// [`push`]: #method.push
+    pub fn begin(&mut self) -> Bytes {
+        let mut buf = BytesMut::new();
+        self.open(&mut buf);
+        buf.freeze()
+    }
+
+    /// Serializes `item` as the next element of the array.
+    ///
+    /// The chunk holds the separating comma if the array already has
+    /// elements, and the opening bracket if it has not been started yet. If
+    /// `item` fails to serialize, nothing is added to the array, and further
+    /// elements may still be pushed.
+    pub fn push(&mut self, item: &T) -> Result<Bytes, CodecError>
+    where
+        T: Serialize,
+    {
+        let mut buf = BytesMut::new();
+        let state = self.state;
+        if !self.open(&mut buf) {
+            buf.extend_from_slice(b",");
+        }
+
+        if let Err(err) = self.json.write(item, &mut buf) {
+            self.state = state;
+            return Err(err);
+        }
+
+        self.state = State::Elements;
+        Ok(buf.freeze())
+    }
+
+    /// Returns the end of the array, i.e. its closing bracket, preceded by
+    /// the opening one if the array has not been started.
+    pub fn finish(mut self) -> Bytes {
+        let mut buf = BytesMut::new();
+        self.open(&mut buf);
+        buf.extend_from_slice(b"]");
+        buf.freeze()
+    }
+
+    /// Returns whether no element has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.state != State::Elements
+    }
+
+    /// Appends the opening bracket to `buf` unless it has been written
+    /// already, returning whether there are no elements yet.
+    fn open(&mut self, buf: &mut BytesMut) -> bool {
+        match self.state {
+            State::Initial => {
+                buf.extend_from_slice(b"[");
+                self.state = State::Empty;
+                true
+            }
+            State::Empty => true,
+            State::Elements => false,
+        }
+    }
+}
+
+impl<T> Default for JsonArrayWriter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for JsonArrayWriter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonArrayWriter")
+            .field("json", &self.json)
+            .field("state", &self.state)
+            .finish()
+    }
+}
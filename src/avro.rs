@@ -0,0 +1,163 @@
+use apache_avro::Schema;
+use bytes::Bytes;
+use serde::{de, Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of Avro encoded buffers to a stream of values.
+pub type ReadAvro<T, U> = FramedRead<T, U, Avro<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as Avro.
+pub type WriteAvro<T, U> = FramedWrite<T, U, Avro<U>>;
+
+/// Avro serializer and deserializer backed by [apache-avro].
+///
+/// Every frame holds a single Avro datum written with the schema of the
+/// codec, without the schema itself or any header, so both peers must use
+/// the same schema. Values are checked against the schema before being
+/// written, converting them where Avro's schema resolution rules allow it,
+/// e.g. from `int` to `long`. Values that do not match the schema, and
+/// frames whose datum does not fit `T`, fail with [`CodecError::AvroSchema`].
+/// Malformed frames, including ones with bytes left over after the datum,
+/// and values serde cannot convert to Avro fail with [`CodecError::Avro`].
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use apache_avro::Schema;
+/// use serde::{de, Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::{Avro, CodecError};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct User {
+///     name: String,
+///     age: i32,
+/// }
+///
+/// let schema = Schema::parse_str(
+///     r#"{
+///         "type": "record",
+///         "name": "User",
+///         "fields": [
+///             { "name": "name", "type": "string" },
+///             { "name": "age", "type": "int" }
+///         ]
+///     }"#,
+/// )
+/// .unwrap();
+///
+/// let mut codec = Avro::<User>::new(schema.clone());
+///
+/// let user = User { name: "Jane".into(), age: 43 };
+/// let frame = Pin::new(&mut codec).serialize(&user).unwrap();
+/// assert_eq!(frame, &b"\x08Jane\x56"[..]);
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), user);
+///
+/// // A value lacking a field of the schema
+/// #[derive(Serialize)]
+/// struct Anonymous {
+///     age: i32,
+/// }
+///
+/// match Pin::new(&mut Avro::new(schema.clone())).serialize(&Anonymous { age: 43 }) {
+///     Err(CodecError::AvroSchema(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+///
+/// // A type expecting a field the datum lacks
+/// #[derive(Debug, Deserialize)]
+/// struct Account {
+///     name: String,
+///     email: String,
+/// }
+///
+/// let mut accounts = Avro::<Account>::new(schema);
+/// match Pin::new(&mut accounts).deserialize(&(&b"\x08Jane\x56"[..]).into()) {
+///     Err(CodecError::AvroSchema(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+///
+/// // Bytes following the datum
+/// match Pin::new(&mut codec).deserialize(&(&b"\x08Jane\x56\x00"[..]).into()) {
+///     Err(CodecError::Avro(err)) => assert!(err.to_string().contains("unexpected bytes")),
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [apache-avro]: https://docs.rs/apache-avro
+/// [`CodecError::AvroSchema`]: enum.CodecError.html#variant.AvroSchema
+/// [`CodecError::Avro`]: enum.CodecError.html#variant.Avro
+pub struct Avro<T> {
+    schema: Schema,
+    ghost: PhantomData<T>,
+}
+
+impl<T> Avro<T> {
+    /// Creates a new Avro serializer and deserializer writing and reading
+    /// values with `schema`.
+    pub fn new(schema: Schema) -> Self {
+        Avro {
+            schema,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns the schema values are written and read with.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+}
+
+impl<T> Clone for Avro<T> {
+    fn clone(&self) -> Self {
+        Avro::new(self.schema.clone())
+    }
+}
+
+impl<T> fmt::Debug for Avro<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Avro")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Avro<T> {
+    const NAME: &'static str = "avro";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        let value = apache_avro::to_value(item)?
+            .resolve(&self.schema)
+            .map_err(|err| CodecError::AvroSchema(Box::new(err)))?;
+
+        Ok(apache_avro::to_avro_datum(&self.schema, value)?.into())
+    }
+
+    fn decode(&self, mut src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        let value = apache_avro::from_avro_datum(&self.schema, &mut src, None)?;
+        if !src.is_empty() {
+            let err = <apache_avro::Error as de::Error>::custom("unexpected bytes after the datum");
+            return Err(err.into());
+        }
+
+        apache_avro::from_value(&value).map_err(|err| CodecError::AvroSchema(Box::new(err)))
+    }
+}
+
+impl_symmetrical_codec!(Avro);
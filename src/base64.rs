@@ -0,0 +1,117 @@
+use bytes::{Bytes, BytesMut};
+use pin_project::pin_project;
+use tokio_serde::{Deserializer, Serializer};
+
+use std::pin::Pin;
+
+use crate::CodecError;
+
+/// Serializer and deserializer encoding the frames of another one as
+/// [base64].
+///
+/// Values are serialized by the inner serializer, and the resulting frame is
+/// base64 encoded, so that binary formats such as MessagePack or bincode can
+/// be sent over transports which only carry text, e.g. server-sent events.
+/// Incoming frames are decoded before being passed to the inner
+/// deserializer.
+///
+/// The URL-safe alphabet is used by default, while [`standard`] creates a
+/// `Base64` using the standard one. In both cases the output is padded.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::{Base64, Json};
+///
+/// let mut codec = Base64::new(Json::<Vec<u8>>::new());
+///
+/// let frame = Pin::new(&mut codec).serialize(&vec![1, 2, 3]).unwrap();
+/// let line = String::from_utf8(frame.to_vec()).unwrap();
+/// assert_eq!(line, "WzEsMiwzXQ==");
+///
+/// let value = Pin::new(&mut codec).deserialize(&line.into()).unwrap();
+/// assert_eq!(value, vec![1, 2, 3]);
+/// ```
+///
+/// [base64]: https://docs.rs/base64
+/// [`standard`]: #method.standard
+#[pin_project]
+#[derive(Clone, Debug)]
+pub struct Base64<C> {
+    #[pin]
+    inner: C,
+    config: base64::Config,
+}
+
+impl<C> Base64<C> {
+    /// Creates a new serializer and deserializer encoding the frames of
+    /// `inner` with the URL-safe alphabet.
+    pub fn new(inner: C) -> Self {
+        Base64 {
+            inner,
+            config: base64::URL_SAFE,
+        }
+    }
+
+    /// Creates a new serializer and deserializer encoding the frames of
+    /// `inner` with the standard alphabet.
+    pub fn standard(inner: C) -> Self {
+        Base64 {
+            inner,
+            config: base64::STANDARD,
+        }
+    }
+
+    /// Returns a reference to the inner serializer and deserializer.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner serializer and deserializer.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Consumes the `Base64`, returning the inner serializer and
+    /// deserializer.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: Default> Default for Base64<C> {
+    fn default() -> Self {
+        Self::new(C::default())
+    }
+}
+
+impl<T, C> Deserializer<T> for Base64<C>
+where
+    C: Deserializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        let this = self.project();
+        let buf = base64::decode_config(&src[..], *this.config)?;
+        this.inner.deserialize(&buf.into()).map_err(Into::into)
+    }
+}
+
+impl<T, C> Serializer<T> for Base64<C>
+where
+    C: Serializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        let this = self.project();
+        let frame = this.inner.serialize(item).map_err(Into::into)?;
+        Ok(base64::encode_config(&frame, *this.config).into())
+    }
+}
@@ -0,0 +1,105 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of bincode encoded buffers to a stream of values.
+pub type ReadBincode<T, U> = FramedRead<T, U, Bincode<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values with bincode.
+pub type WriteBincode<T, U> = FramedWrite<T, U, Bincode<U>>;
+
+/// Bincode serializer and deserializer backed by [bincode].
+///
+/// Bincode output carries no framing of its own and decoding relies on the
+/// exact length of the input, so this must be paired with a framing layer
+/// such as `LengthDelimitedCodec` where each frame holds exactly one value.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Bincode;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Heartbeat {
+///     node: u16,
+///     term: u64,
+///     peers: Vec<String>,
+/// }
+///
+/// let heartbeat = Heartbeat {
+///     node: 3,
+///     term: 42,
+///     peers: vec!["a".into(), "b".into()],
+/// };
+///
+/// let mut codec = Bincode::<Heartbeat>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&heartbeat).unwrap();
+/// // 2 bytes of node, 8 of term, then an 8 byte length and 9 bytes per peer
+/// assert_eq!(frame.len(), 2 + 8 + 8 + 2 * 9);
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), heartbeat);
+/// ```
+///
+/// [bincode]: https://docs.rs/bincode
+pub struct Bincode<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> Bincode<T> {
+    /// Creates a new bincode serializer and deserializer.
+    pub fn new() -> Self {
+        Bincode { ghost: PhantomData }
+    }
+}
+
+impl<T> Default for Bincode<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Bincode<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Bincode<T> {}
+
+impl<T> fmt::Debug for Bincode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bincode").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Bincode<T> {
+    const NAME: &'static str = "bincode";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        Ok(bincode::serialize(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(bincode::deserialize(src)?)
+    }
+}
+
+impl_symmetrical_codec!(Bincode);
@@ -0,0 +1,113 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of BSON encoded buffers to a stream of values.
+pub type ReadBson<T, U> = FramedRead<T, U, Bson<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as BSON.
+pub type WriteBson<T, U> = FramedWrite<T, U, Bson<U>>;
+
+/// BSON serializer and deserializer backed by [bson].
+///
+/// Every BSON document starts with its own length, so a peer that honours
+/// this header can exchange these frames without any extra length
+/// delimiting. The [`ReadBson`] and [`WriteBson`] aliases are still provided
+/// for use over length-delimited framing. As with any BSON document, only
+/// map-like values can be sent at the top level.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bson::{spec::BinarySubtype, Binary, DateTime};
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Bson;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Attachment {
+///     name: String,
+///     contents: Binary,
+///     uploaded: DateTime,
+/// }
+///
+/// let attachment = Attachment {
+///     name: "logo.png".into(),
+///     contents: Binary {
+///         subtype: BinarySubtype::Generic,
+///         bytes: vec![0x89, b'P', b'N', b'G'],
+///     },
+///     uploaded: DateTime::from_millis(1_600_000_000_000),
+/// };
+///
+/// let mut codec = Bson::<Attachment>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&attachment).unwrap();
+/// // The document starts with its length in little endian byte order
+/// assert_eq!(frame[..4], (frame.len() as u32).to_le_bytes());
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), attachment);
+/// ```
+///
+/// [bson]: https://docs.rs/bson
+/// [`ReadBson`]: type.ReadBson.html
+/// [`WriteBson`]: type.WriteBson.html
+pub struct Bson<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> Bson<T> {
+    /// Creates a new BSON serializer and deserializer.
+    pub fn new() -> Self {
+        Bson { ghost: PhantomData }
+    }
+}
+
+impl<T> Default for Bson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Bson<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Bson<T> {}
+
+impl<T> fmt::Debug for Bson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bson").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Bson<T> {
+    const NAME: &'static str = "bson";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        Ok(bson::to_vec(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(bson::from_slice(src)?)
+    }
+}
+
+impl_symmetrical_codec!(Bson);
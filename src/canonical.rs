@@ -0,0 +1,192 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    json::from_slice,
+    CodecError,
+};
+
+/// JSON serializer producing canonical output as specified by the JSON
+/// Canonicalization Scheme ([RFC 8785]).
+///
+/// The same value always serializes to the same bytes, which makes the
+/// output suitable for hashing or signing. Object keys are sorted by their
+/// UTF-16 code units, no whitespace is written, strings are escaped
+/// minimally and numbers are formatted the way ECMAScript does, which
+/// includes writing integers as `f64`. Non-finite floats are written as
+/// `null`, just like serde_json does. Deserialization behaves exactly like
+/// [`Json`].
+///
+/// Values are first converted to a `serde_json::Value`, so this is slower
+/// than [`Json`]. Note that unless serde_json's `float_roundtrip` feature is
+/// enabled, floats parsed from JSON text may be off by the last bit, and thus
+/// not serialize back to their canonical form.
+///
+/// # Examples
+///
+/// The example of section 3.2.2 of RFC 8785:
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde_json::json;
+/// use tokio_serde::Serializer;
+/// use tokio_serde_codecs::CanonicalJson;
+///
+/// let value = json!({
+///     "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+///     "string": "\u{20ac}$\u{000F}\u{000a}A'\u{0042}\u{0022}\u{005c}\\\"/",
+///     "literals": [null, true, false]
+/// });
+///
+/// let frame = Pin::new(&mut CanonicalJson::new()).serialize(&value).unwrap();
+/// assert_eq!(
+///     std::str::from_utf8(&frame).unwrap(),
+///     r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+/// );
+/// ```
+///
+/// [RFC 8785]: https://tools.ietf.org/html/rfc8785
+/// [`Json`]: struct.Json.html
+pub struct CanonicalJson<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> CanonicalJson<T> {
+    /// Creates a new canonical JSON serializer and deserializer.
+    pub fn new() -> Self {
+        CanonicalJson { ghost: PhantomData }
+    }
+}
+
+impl<T> Default for CanonicalJson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for CanonicalJson<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CanonicalJson<T> {}
+
+impl<T> fmt::Debug for CanonicalJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CanonicalJson").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for CanonicalJson<T> {
+    const NAME: &'static str = "json";
+    const JSON: bool = true;
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        let mut buf = Vec::new();
+        write_value(&mut buf, &serde_json::to_value(item)?)?;
+        Ok(buf.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        from_slice(src)
+    }
+}
+
+impl_symmetrical_codec!(CanonicalJson);
+
+fn write_value(buf: &mut Vec<u8>, value: &Value) -> Result<(), serde_json::Error> {
+    match *value {
+        Value::Null => buf.extend_from_slice(b"null"),
+        Value::Bool(b) => buf.extend_from_slice(if b { b"true" } else { b"false" }),
+        Value::Number(ref n) => match n.as_f64() {
+            Some(n) if n.is_finite() => write_number(buf, n),
+            _ => buf.extend_from_slice(b"null"),
+        },
+        // serde_json escapes strings exactly as required by the scheme.
+        Value::String(ref s) => serde_json::to_writer(&mut *buf, s)?,
+        Value::Array(ref values) => {
+            buf.push(b'[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    buf.push(b',');
+                }
+                write_value(buf, value)?;
+            }
+            buf.push(b']');
+        }
+        Value::Object(ref map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            buf.push(b'{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    buf.push(b',');
+                }
+                serde_json::to_writer(&mut *buf, key)?;
+                buf.push(b':');
+                write_value(buf, value)?;
+            }
+            buf.push(b'}');
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a finite number the way ECMAScript's `Number.prototype.toString`
+/// does.
+fn write_number(buf: &mut Vec<u8>, n: f64) {
+    if n == 0.0 {
+        // Also covers negative zero
+        buf.push(b'0');
+        return;
+    }
+
+    if n < 0.0 {
+        buf.push(b'-');
+    }
+
+    // Rust formats the shortest digits that round-trip, like ECMAScript does,
+    // so only their placement needs to be adjusted.
+    let sci = format!("{:e}", n.abs());
+    let (mantissa, exponent) = sci.split_at(sci.find('e').unwrap());
+    let digits: Vec<u8> = mantissa.bytes().filter(|&b| b != b'.').collect();
+    let k = digits.len() as i32;
+    // Position of the decimal point relative to the start of the digits
+    let n = exponent[1..].parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        buf.extend_from_slice(&digits);
+        buf.resize(buf.len() + (n - k) as usize, b'0');
+    } else if 0 < n && n <= 21 {
+        buf.extend_from_slice(&digits[..n as usize]);
+        buf.push(b'.');
+        buf.extend_from_slice(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        buf.extend_from_slice(b"0.");
+        buf.resize(buf.len() + (-n) as usize, b'0');
+        buf.extend_from_slice(&digits);
+    } else {
+        buf.push(digits[0]);
+        if k > 1 {
+            buf.push(b'.');
+            buf.extend_from_slice(&digits[1..]);
+        }
+        buf.push(b'e');
+        buf.push(if n > 0 { b'+' } else { b'-' });
+        buf.extend_from_slice((n - 1).abs().to_string().as_bytes());
+    }
+}
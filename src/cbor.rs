@@ -0,0 +1,233 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of CBOR encoded buffers to a stream of values.
+pub type ReadCbor<T, U> = FramedRead<T, U, Cbor<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as CBOR.
+pub type WriteCbor<T, U> = FramedWrite<T, U, Cbor<U>>;
+
+/// CBOR serializer and deserializer backed by [ciborium].
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::{Cbor, CodecError};
+///
+/// let mut codec = Cbor::<Vec<u32>>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&vec![1, 2, 3]).unwrap();
+/// assert_eq!(frame, &[0x83, 0x01, 0x02, 0x03][..]);
+///
+/// let value = Pin::new(&mut codec).deserialize(&frame.into()).unwrap();
+/// assert_eq!(value, [1, 2, 3]);
+///
+/// // A frame holding a second item after the first one is rejected
+/// let frame = BytesMut::from(&[0x83, 0x01, 0x02, 0x03, 0x80][..]);
+/// match Pin::new(&mut codec).deserialize(&frame) {
+///     Err(CodecError::CborDecode(err)) => {
+///         assert!(err.to_string().contains("unexpected bytes"), "{}", err);
+///     }
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// Structs with nested maps and flattened fields round-trip as well, since
+/// CBOR describes its own structure:
+///
+/// ```
+/// use std::{collections::BTreeMap, pin::Pin};
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Cbor;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Metadata {
+///     firmware: String,
+///     uptime: u64,
+/// }
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Device {
+///     id: u32,
+///     sensors: BTreeMap<String, BTreeMap<String, f64>>,
+///     #[serde(flatten)]
+///     metadata: Metadata,
+/// }
+///
+/// let mut sensors = BTreeMap::new();
+/// let mut temperature = BTreeMap::new();
+/// temperature.insert("celsius".to_owned(), 21.5);
+/// temperature.insert("threshold".to_owned(), 30.0);
+/// sensors.insert("temperature".to_owned(), temperature);
+///
+/// let device = Device {
+///     id: 7,
+///     sensors,
+///     metadata: Metadata { firmware: "1.4.2".into(), uptime: 86_400 },
+/// };
+///
+/// let mut codec = Cbor::<Device>::new();
+/// let frame = Pin::new(&mut codec).serialize(&device).unwrap();
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), device);
+/// ```
+///
+/// Choosing between JSON and CBOR at runtime through a [`DynCodec`]:
+///
+/// ```
+/// use serde_json::json;
+/// use tokio_serde_codecs::{Cbor, DynCodec, Json};
+///
+/// fn codec(binary: bool) -> Box<dyn DynCodec> {
+///     if binary {
+///         Box::new(Cbor::new())
+///     } else {
+///         Box::new(Json::new())
+///     }
+/// }
+///
+/// let value = json!({ "id": 1 });
+///
+/// let json = codec(false);
+/// let frame = json.encode(&value).unwrap();
+/// assert_eq!(frame, &br#"{"id":1}"#[..]);
+/// assert_eq!(json.decode(&frame).unwrap(), value);
+///
+/// let cbor = codec(true);
+/// let frame = cbor.encode(&value).unwrap();
+/// assert_eq!(frame, &[0xA1, 0x62, b'i', b'd', 0x01][..]);
+/// assert_eq!(cbor.decode(&frame).unwrap(), value);
+/// ```
+///
+/// [ciborium]: https://docs.rs/ciborium
+/// [`DynCodec`]: trait.DynCodec.html
+pub struct Cbor<T> {
+    self_describing: bool,
+    ghost: PhantomData<T>,
+}
+
+/// The self-described CBOR tag 55799, marking the data following it as CBOR
+/// without changing its meaning.
+const SELF_DESCRIBE_TAG: [u8; 3] = [0xD9, 0xD9, 0xF7];
+
+impl<T> Cbor<T> {
+    /// Creates a new CBOR serializer and deserializer.
+    pub fn new() -> Self {
+        Cbor {
+            self_describing: false,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets whether serialized frames start with the self-described CBOR tag
+    /// (`0xD9D9F7`, tag 55799 of [RFC 8949]), which lets consumers recognize
+    /// the data as CBOR.
+    ///
+    /// The tag is left out by default to keep frames compact. It is skipped
+    /// when deserializing regardless of this setting, so peers disagreeing on
+    /// it still understand each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use tokio_serde::{Deserializer, Serializer};
+    /// use tokio_serde_codecs::Cbor;
+    ///
+    /// let mut tagged = Cbor::<Vec<u32>>::new().self_describing(true);
+    ///
+    /// let frame = Pin::new(&mut tagged).serialize(&vec![1, 2, 3]).unwrap();
+    /// assert_eq!(frame, &[0xD9, 0xD9, 0xF7, 0x83, 0x01, 0x02, 0x03][..]);
+    ///
+    /// let frame = frame.into();
+    /// assert_eq!(Pin::new(&mut tagged).deserialize(&frame).unwrap(), [1, 2, 3]);
+    ///
+    /// // A peer not writing the tag still reads it
+    /// let mut plain = Cbor::<Vec<u32>>::new();
+    /// assert_eq!(Pin::new(&mut plain).deserialize(&frame).unwrap(), [1, 2, 3]);
+    /// ```
+    ///
+    /// [RFC 8949]: https://www.rfc-editor.org/rfc/rfc8949#section-3.4.6
+    pub fn self_describing(mut self, self_describing: bool) -> Self {
+        self.self_describing = self_describing;
+        self
+    }
+}
+
+impl<T> Default for Cbor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Cbor<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Cbor<T> {}
+
+impl<T> fmt::Debug for Cbor<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cbor")
+            .field("self_describing", &self.self_describing)
+            .finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Cbor<T> {
+    const NAME: &'static str = "cbor";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        let mut buf = Vec::new();
+        if self.self_describing {
+            buf.extend_from_slice(&SELF_DESCRIBE_TAG);
+        }
+        ciborium::ser::into_writer(item, &mut buf)?;
+        Ok(buf.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        let mut rest = if src.starts_with(&SELF_DESCRIBE_TAG) {
+            &src[SELF_DESCRIBE_TAG.len()..]
+        } else {
+            src
+        };
+        let value = ciborium::de::from_reader(&mut rest)?;
+
+        // Like the JSON codecs, reject anything following the value
+        if !rest.is_empty() {
+            let offset = src.len() - rest.len();
+            return Err(ciborium::de::Error::Semantic(
+                Some(offset),
+                "unexpected bytes after the item".into(),
+            )
+            .into());
+        }
+
+        Ok(value)
+    }
+}
+
+impl_symmetrical_codec!(Cbor);
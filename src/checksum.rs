@@ -0,0 +1,188 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use pin_project::pin_project;
+use tokio_serde::{Deserializer, Serializer};
+
+use std::pin::Pin;
+
+use crate::{ChecksumMismatch, CodecError};
+
+/// Length of the checksum appended to each frame by [`Checked`].
+///
+/// [`Checked`]: struct.Checked.html
+const CHECKSUM_LEN: usize = 4;
+
+/// Checksum algorithm used by [`Checked`].
+///
+/// [`Checked`]: struct.Checked.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    /// CRC-32 as used by Ethernet, gzip and PNG, computed with [crc32fast].
+    ///
+    /// [crc32fast]: https://docs.rs/crc32fast
+    #[default]
+    Crc32,
+    /// The 32 bit variant of xxHash with a seed of 0, computed with
+    /// [xxhash-rust].
+    ///
+    /// [xxhash-rust]: https://docs.rs/xxhash-rust
+    XxHash32,
+}
+
+impl ChecksumAlgorithm {
+    /// Returns the checksum of `src`.
+    fn checksum(self, src: &[u8]) -> u32 {
+        match self {
+            ChecksumAlgorithm::Crc32 => crc32fast::hash(src),
+            ChecksumAlgorithm::XxHash32 => xxhash_rust::xxh32::xxh32(src, 0),
+        }
+    }
+
+    /// Returns the name of the algorithm for error messages.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32 => "CRC-32",
+            ChecksumAlgorithm::XxHash32 => "xxHash32",
+        }
+    }
+}
+
+/// Serializer and deserializer appending a checksum to the frames of another
+/// one.
+///
+/// Every serialized frame is the frame of the inner serializer followed by a
+/// 32 bit checksum of it in big endian byte order. When deserializing, the
+/// checksum is verified and stripped before the rest is passed to the inner
+/// deserializer. Frames which have been corrupted on the way are rejected
+/// with a [`ChecksumMismatch`] error, instead of failing to parse or, worse,
+/// being parsed into a different value. Both peers must agree on the
+/// [algorithm].
+///
+/// The checksum guards against accidental corruption only. It does not
+/// authenticate the frames, as anyone able to modify a frame can compute a
+/// matching checksum as well.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::{Checked, CodecError, Json};
+///
+/// let mut codec = Checked::new(Json::<Vec<i32>>::new());
+///
+/// let frame = Pin::new(&mut codec).serialize(&vec![1, 2, 3]).unwrap();
+/// assert_eq!(frame.len(), b"[1,2,3]".len() + 4);
+///
+/// let mut frame = BytesMut::from(frame);
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame).unwrap(), [1, 2, 3]);
+///
+/// // Flip a bit of the JSON, turning it into a frame that does not parse
+/// frame[0] ^= 0x20;
+/// match Pin::new(&mut codec).deserialize(&frame) {
+///     Err(CodecError::Checksum(err)) => {
+///         assert_ne!(err.expected(), err.actual().unwrap());
+///     }
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [`ChecksumMismatch`]: struct.ChecksumMismatch.html
+/// [algorithm]: #method.algorithm
+#[pin_project]
+#[derive(Clone, Debug, Default)]
+pub struct Checked<C> {
+    #[pin]
+    inner: C,
+    algorithm: ChecksumAlgorithm,
+}
+
+impl<C> Checked<C> {
+    /// Creates a new serializer and deserializer appending a CRC-32 to the
+    /// frames of `inner`.
+    pub fn new(inner: C) -> Self {
+        Checked {
+            inner,
+            algorithm: ChecksumAlgorithm::default(),
+        }
+    }
+
+    /// Sets the checksum algorithm.
+    pub fn algorithm(mut self, algorithm: ChecksumAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Returns a reference to the inner serializer and deserializer.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner serializer and deserializer.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Consumes the `Checked`, returning the inner serializer and
+    /// deserializer.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<T, C> Deserializer<T> for Checked<C>
+where
+    C: Deserializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        let this = self.project();
+        let algorithm = *this.algorithm;
+
+        if src.len() < CHECKSUM_LEN {
+            return Err(ChecksumMismatch {
+                algorithm,
+                expected: algorithm.checksum(src),
+                actual: None,
+            }
+            .into());
+        }
+
+        let (contents, checksum) = src.split_at(src.len() - CHECKSUM_LEN);
+        let expected = algorithm.checksum(contents);
+        let actual = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+        if actual != expected {
+            return Err(ChecksumMismatch {
+                algorithm,
+                expected,
+                actual: Some(actual),
+            }
+            .into());
+        }
+
+        this.inner
+            .deserialize(&BytesMut::from(contents))
+            .map_err(Into::into)
+    }
+}
+
+impl<T, C> Serializer<T> for Checked<C>
+where
+    C: Serializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        let this = self.project();
+        let frame = this.inner.serialize(item).map_err(Into::into)?;
+
+        let mut buf = BytesMut::with_capacity(frame.len() + CHECKSUM_LEN);
+        buf.extend_from_slice(&frame);
+        buf.put_u32_be(this.algorithm.checksum(&frame));
+        Ok(buf.freeze())
+    }
+}
@@ -0,0 +1,232 @@
+use bytes::{Bytes, BytesMut};
+use flate2::{
+    read::{DeflateDecoder, GzDecoder},
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use pin_project::pin_project;
+use tokio_serde::{Deserializer, Serializer};
+
+use std::{
+    io::{self, Read, Write},
+    pin::Pin,
+};
+
+use crate::CodecError;
+
+/// Default of [`Compressed::max_decompressed_len`], the same as the default
+/// maximum frame length of `LengthDelimitedCodec`.
+///
+/// [`Compressed::max_decompressed_len`]: struct.Compressed.html#method.max_decompressed_len
+const DEFAULT_MAX_DECOMPRESSED_LEN: usize = 8 * 1024 * 1024;
+
+/// Compression format used by [`Compressed`].
+///
+/// [`Compressed`]: struct.Compressed.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompressionFormat {
+    /// gzip, including its header and checksum.
+    #[default]
+    Gzip,
+    /// Raw deflate, without any header.
+    Deflate,
+}
+
+/// Serializer and deserializer compressing the frames of another one using
+/// [flate2].
+///
+/// Values are serialized by the inner serializer, and the resulting frame is
+/// compressed before being handed to the transport. Incoming frames are
+/// decompressed before being passed to the inner deserializer. This keeps
+/// the message types unchanged while trading CPU time for bandwidth, which
+/// pays off for large and repetitive payloads. Both peers must agree on the
+/// [format].
+///
+/// The framing layer only limits the length of the compressed frames, and a
+/// small frame can decompress to a very large one. Decompression therefore
+/// stops once a frame grows beyond its [maximum decompressed length], 8 MB
+/// by default, so that peers cannot exhaust memory by sending decompression
+/// bombs. Limits such as [`Json::max_len`] apply after decompression.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::{Compressed, Json};
+///
+/// let value = vec!["the same string over and over".to_string(); 100];
+///
+/// let json = Pin::new(&mut Json::<Vec<String>>::new()).serialize(&value).unwrap();
+///
+/// let mut codec = Compressed::new(Json::<Vec<String>>::new());
+/// let compressed = Pin::new(&mut codec).serialize(&value).unwrap();
+/// assert!(compressed.len() < json.len());
+///
+/// let decoded = Pin::new(&mut codec).deserialize(&compressed.into()).unwrap();
+/// assert_eq!(decoded, value);
+/// ```
+///
+/// [flate2]: https://docs.rs/flate2
+/// [format]: #method.format
+/// [maximum decompressed length]: #method.max_decompressed_len
+/// [`Json::max_len`]: struct.Json.html#method.max_len
+#[pin_project]
+#[derive(Clone, Debug)]
+pub struct Compressed<C> {
+    #[pin]
+    inner: C,
+    format: CompressionFormat,
+    level: Compression,
+    max_decompressed_len: usize,
+}
+
+impl<C> Compressed<C> {
+    /// Creates a new serializer and deserializer compressing the frames of
+    /// `inner` with gzip at the default level.
+    pub fn new(inner: C) -> Self {
+        Compressed {
+            inner,
+            format: CompressionFormat::default(),
+            level: Compression::default(),
+            max_decompressed_len: DEFAULT_MAX_DECOMPRESSED_LEN,
+        }
+    }
+
+    /// Sets the compression format.
+    pub fn format(mut self, format: CompressionFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the compression level, from 0 (no compression) to 9 (best
+    /// compression).
+    ///
+    /// The level only affects serialization. It defaults to 6.
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = Compression::new(level);
+        self
+    }
+
+    /// Sets the maximum length in bytes of a frame after decompression,
+    /// 8 MB by default.
+    ///
+    /// Decompression stops as soon as a frame exceeds this length, failing
+    /// with a [`CodecError::Compression`] of kind `InvalidData`, so that no
+    /// more than this is ever buffered for one frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use tokio_serde::{Deserializer, Serializer};
+    /// use tokio_serde_codecs::{CodecError, Compressed, Json};
+    ///
+    /// // A megabyte of zeros compresses to about a kilobyte
+    /// let value = vec![0u8; 1024 * 1024];
+    /// let mut sender = Compressed::new(Json::<Vec<u8>>::new());
+    /// let frame = Pin::new(&mut sender).serialize(&value).unwrap();
+    /// assert!(frame.len() < 8 * 1024);
+    ///
+    /// let mut receiver = Compressed::new(Json::<Vec<u8>>::new()).max_decompressed_len(64 * 1024);
+    /// match Pin::new(&mut receiver).deserialize(&frame.into()) {
+    ///     Err(CodecError::Compression(err)) => {
+    ///         assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    ///
+    /// [`CodecError::Compression`]: enum.CodecError.html#variant.Compression
+    pub fn max_decompressed_len(mut self, max_decompressed_len: usize) -> Self {
+        self.max_decompressed_len = max_decompressed_len;
+        self
+    }
+
+    /// Returns a reference to the inner serializer and deserializer.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner serializer and deserializer.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Consumes the `Compressed`, returning the inner serializer and
+    /// deserializer.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: Default> Default for Compressed<C> {
+    fn default() -> Self {
+        Self::new(C::default())
+    }
+}
+
+impl<T, C> Deserializer<T> for Compressed<C>
+where
+    C: Deserializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        let this = self.project();
+        let max_len = *this.max_decompressed_len;
+        let mut buf = Vec::new();
+
+        // Reading one byte beyond the limit tells frames of exactly the
+        // maximum length apart from longer ones
+        let limit = max_len as u64 + 1;
+        match *this.format {
+            CompressionFormat::Gzip => GzDecoder::new(&src[..]).take(limit).read_to_end(&mut buf),
+            CompressionFormat::Deflate => DeflateDecoder::new(&src[..])
+                .take(limit)
+                .read_to_end(&mut buf),
+        }
+        .map_err(CodecError::Compression)?;
+
+        if buf.len() > max_len {
+            return Err(CodecError::Compression(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "decompressed frame exceeds maximum length of {} bytes",
+                    max_len
+                ),
+            )));
+        }
+
+        this.inner.deserialize(&buf.into()).map_err(Into::into)
+    }
+}
+
+impl<T, C> Serializer<T> for Compressed<C>
+where
+    C: Serializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        let this = self.project();
+        let frame = this.inner.serialize(item).map_err(Into::into)?;
+
+        match *this.format {
+            CompressionFormat::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), *this.level);
+                encoder.write_all(&frame).and_then(|()| encoder.finish())
+            }
+            CompressionFormat::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), *this.level);
+                encoder.write_all(&frame).and_then(|()| encoder.finish())
+            }
+        }
+        .map(Into::into)
+        .map_err(CodecError::Compression)
+    }
+}
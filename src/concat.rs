@@ -0,0 +1,221 @@
+use bytes::BytesMut;
+use serde::{Deserialize, Serialize};
+use tokio_codec::{Decoder, Encoder};
+
+use std::{fmt, io, marker::PhantomData};
+
+use crate::{CodecError, JsonError};
+
+/// Codec for concatenated JSON objects and arrays.
+///
+/// Values follow each other without any delimiter, e.g.
+/// `{"a":1}{"a":2}[3]`, optionally separated by whitespace. The end of a
+/// value is found by tracking the nesting of braces and brackets, skipping
+/// those inside strings. A value that has not been completed yet stays
+/// buffered until the rest of it arrives, and is not scanned again from the
+/// start. As a value is buffered until it is complete, setting a [maximum
+/// value length] is recommended when reading from untrusted peers.
+///
+/// Only objects and arrays can be delimited this way, because how far a
+/// number extends cannot be told before the next byte has arrived. Other
+/// values at the top level are rejected both when decoding and encoding.
+///
+/// Errors are reported as a [`CodecError`], the same as with [`NdJson`]:
+/// values which do not parse fail with [`CodecError::Json`], while errors of
+/// the transport, overlong values and values other than objects and arrays
+/// fail with [`CodecError::Io`].
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use serde_json::{json, Value};
+/// use tokio_codec::Decoder;
+/// use tokio_serde_codecs::ConcatJson;
+///
+/// let mut codec = ConcatJson::<Value>::new();
+/// let mut buf = BytesMut::from(&br#"{"a":"}"} {"b":"\"}"#[..]);
+///
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({"a": "}"})));
+/// assert_eq!(codec.decode(&mut buf).unwrap(), None);
+///
+/// buf.extend_from_slice(br#""}"#);
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({"b": "\"}"})));
+/// ```
+///
+/// A value which is delimited but does not parse:
+///
+/// ```
+/// use bytes::BytesMut;
+/// use serde_json::Value;
+/// use tokio_codec::Decoder;
+/// use tokio_serde_codecs::{CodecError, ConcatJson};
+///
+/// let mut codec = ConcatJson::<Value>::new();
+/// let mut buf = BytesMut::from(&br#"{"a":}{"b":2}"#[..]);
+///
+/// match codec.decode(&mut buf) {
+///     Err(CodecError::Json(err)) => assert_eq!(err.frame_len(), Some(6)),
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+///
+/// // The following value is still read
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(serde_json::json!({"b": 2})));
+/// ```
+///
+/// [`CodecError`]: enum.CodecError.html
+/// [`CodecError::Json`]: enum.CodecError.html#variant.Json
+/// [`CodecError::Io`]: enum.CodecError.html#variant.Io
+/// [`NdJson`]: struct.NdJson.html
+/// [maximum value length]: #method.max_len
+pub struct ConcatJson<T> {
+    // Scan state of the value at the start of the buffer, so that a partial
+    // value is not scanned again on every read. `next_index` is 0 while no
+    // value has been started.
+    next_index: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    max_len: Option<usize>,
+    ghost: PhantomData<T>,
+}
+
+impl<T> ConcatJson<T> {
+    /// Creates a new concatenated JSON codec.
+    pub fn new() -> Self {
+        ConcatJson {
+            next_index: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            max_len: None,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length in bytes of a value.
+    ///
+    /// Decoding fails as soon as more bytes than this have been buffered for
+    /// a single value, instead of waiting for the value to end. There is no
+    /// limit by default.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), CodecError> {
+        match self.max_len {
+            Some(max_len) if len > max_len => Err(CodecError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("value exceeds maximum length of {} bytes", max_len),
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Scans `src` from where the previous call stopped, returning the
+    /// length of the value once it is complete.
+    fn scan(&mut self, src: &[u8]) -> Option<usize> {
+        for (i, &b) in src.iter().enumerate().skip(self.next_index) {
+            if self.in_string {
+                match b {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+            } else {
+                match b {
+                    b'"' => self.in_string = true,
+                    b'{' | b'[' => self.depth += 1,
+                    b'}' | b']' => {
+                        self.depth -= 1;
+                        if self.depth == 0 {
+                            self.next_index = 0;
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self.next_index = src.len();
+        None
+    }
+}
+
+impl<T> Default for ConcatJson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for ConcatJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcatJson")
+            .field("max_len", &self.max_len)
+            .finish()
+    }
+}
+
+fn not_delimitable() -> CodecError {
+    CodecError::Io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "only JSON objects and arrays can be concatenated",
+    ))
+}
+
+impl<T> Decoder for ConcatJson<T>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Item = T;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, CodecError> {
+        if self.next_index == 0 {
+            match src.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(start) => src.advance(start),
+                None => {
+                    src.clear();
+                    return Ok(None);
+                }
+            }
+
+            if src[0] != b'{' && src[0] != b'[' {
+                return Err(not_delimitable());
+            }
+        }
+
+        match self.scan(src) {
+            Some(len) => {
+                self.check_len(len)?;
+                let value = src.split_to(len);
+                serde_json::from_slice(&value)
+                    .map(Some)
+                    .map_err(|err| JsonError::new(err, &value).into())
+            }
+            None => {
+                self.check_len(src.len())?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<T: Serialize> Encoder for ConcatJson<T> {
+    type Item = T;
+    type Error = CodecError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), CodecError> {
+        let buf = serde_json::to_vec(&item)?;
+
+        if buf[0] != b'{' && buf[0] != b'[' {
+            return Err(not_delimitable());
+        }
+
+        dst.extend_from_slice(&buf);
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use bytes::Bytes;
+use serde::{ser, Serialize, Serializer};
+use serde_json::Value;
+
+use std::error;
+
+use crate::format::SymmetricalCodec;
+
+/// Error returned by a [`DynCodec`].
+///
+/// [`DynCodec`]: trait.DynCodec.html
+pub type BoxError = Box<dyn error::Error + Send + Sync>;
+
+/// Object safe serializer and deserializer of `serde_json::Value`s.
+///
+/// The `Serializer` and `Deserializer` traits of tokio-serde are generic
+/// over the value type and have an associated error type, so codecs of
+/// different formats cannot be stored behind one trait object. This trait
+/// is implemented by all of them for `serde_json::Value`, which serves as the
+/// common representation of the values, so that e.g. a server can pick the
+/// format to use for a connection once its content type has been
+/// negotiated.
+///
+/// Values are converted from and to the data model of the format by serde,
+/// which only works for formats that describe their own structure. Formats
+/// such as bincode and postcard, which rely on the receiving type to know
+/// what follows, fail to decode into a `Value`.
+///
+/// With the `arbitrary-precision` feature, numbers are written by the JSON
+/// codecs with all of their digits, and by other formats as the integer or
+/// float they would have been read as without the feature. Numbers that
+/// don't fit an `f64` fail to serialize to those formats.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use tokio_serde_codecs::{CanonicalJson, DynCodec, Json};
+///
+/// fn codec(content_type: &str) -> Option<Box<dyn DynCodec>> {
+///     match content_type {
+///         "application/json" => Some(Box::new(Json::new())),
+///         "application/jcs+json" => Some(Box::new(CanonicalJson::new())),
+///         _ => None,
+///     }
+/// }
+///
+/// let codec = codec("application/jcs+json").unwrap();
+///
+/// let frame = codec.encode(&json!({ "b": 2, "a": 1 })).unwrap();
+/// assert_eq!(frame, &br#"{"a":1,"b":2}"#[..]);
+/// assert_eq!(codec.decode(&frame).unwrap(), json!({ "a": 1, "b": 2 }));
+/// ```
+pub trait DynCodec: Send + Sync {
+    /// Serializes `item` into a frame.
+    fn encode(&self, item: &Value) -> Result<Bytes, BoxError>;
+
+    /// Deserializes a value from the frame `src`.
+    fn decode(&self, src: &[u8]) -> Result<Value, BoxError>;
+}
+
+impl<C> DynCodec for C
+where
+    C: SymmetricalCodec<Value> + Send + Sync,
+{
+    fn encode(&self, item: &Value) -> Result<Bytes, BoxError> {
+        if cfg!(feature = "arbitrary-precision") && !C::JSON {
+            return Ok(SymmetricalCodec::encode(self, &Portable(item))?);
+        }
+        Ok(SymmetricalCodec::encode(self, item)?)
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<Value, BoxError> {
+        Ok(SymmetricalCodec::decode(self, src)?)
+    }
+}
+
+/// Serializes a `Value` with its numbers as plain integers and floats.
+///
+/// With serde_json's `arbitrary_precision` feature, which may also be
+/// enabled by another crate, a `Number` serializes as a struct only
+/// serde_json's serializer understands, and which other formats would write
+/// out as is.
+struct Portable<'a>(&'a Value);
+
+impl Serialize for Portable<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Number(number) => {
+                if let Some(v) = number.as_u64() {
+                    serializer.serialize_u64(v)
+                } else if let Some(v) = number.as_i64() {
+                    serializer.serialize_i64(v)
+                } else if let Some(v) = number.as_f64() {
+                    serializer.serialize_f64(v)
+                } else {
+                    Err(ser::Error::custom("number out of range"))
+                }
+            }
+            Value::Array(values) => serializer.collect_seq(values.iter().map(Portable)),
+            Value::Object(map) => {
+                serializer.collect_map(map.iter().map(|(key, value)| (key, Portable(value))))
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use futures::{ready, Sink, Stream, TryStream};
+use pin_project::pin_project;
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// What to do with frames that hold no bytes at all.
+///
+/// Some peers send zero-length frames, e.g. as a keepalive or to mark the end
+/// of a conversation. An empty frame never holds a valid value, so by
+/// default deserializing one fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EmptyFramePolicy {
+    /// Hand empty frames on, so that deserializing them reports an error.
+    ///
+    /// For the JSON deserializers, such an error can be told apart from
+    /// other failures as its [`frame_len`] is `Some(0)`.
+    ///
+    /// [`frame_len`]: struct.JsonError.html#method.frame_len
+    #[default]
+    Error,
+    /// Drop empty frames silently.
+    Skip,
+}
+
+/// Adapts a stream of frames according to an [`EmptyFramePolicy`].
+///
+/// This is meant to sit between the framing layer and a deserializing
+/// adaptor such as [`ReadJson`], since the latter turns every frame into
+/// exactly one value. Like the adaptors of tokio-serde, it forwards `Sink`
+/// calls to the wrapped transport.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use futures::{executor::block_on, stream, TryStreamExt};
+/// use tokio_serde_codecs::{EmptyFramePolicy, EmptyFrames, Json, ReadJson};
+///
+/// let frames = stream::iter(vec![
+///     Ok::<_, std::io::Error>(BytesMut::from(&b"1"[..])),
+///     Ok(BytesMut::new()),
+///     Ok(BytesMut::from(&b"2"[..])),
+/// ]);
+///
+/// let frames = EmptyFrames::new(frames, EmptyFramePolicy::Skip);
+/// let values = ReadJson::<_, i32>::new(frames, Json::new());
+///
+/// assert_eq!(block_on(values.try_collect::<Vec<_>>()).unwrap(), vec![1, 2]);
+/// ```
+///
+/// [`EmptyFramePolicy`]: enum.EmptyFramePolicy.html
+/// [`ReadJson`]: type.ReadJson.html
+#[pin_project]
+#[derive(Debug)]
+pub struct EmptyFrames<S> {
+    #[pin]
+    inner: S,
+    policy: EmptyFramePolicy,
+}
+
+impl<S> EmptyFrames<S> {
+    /// Creates a new adaptor applying `policy` to the frames of `inner`.
+    pub fn new(inner: S, policy: EmptyFramePolicy) -> Self {
+        EmptyFrames { inner, policy }
+    }
+
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consumes the adaptor, returning the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for EmptyFrames<S>
+where
+    S: TryStream,
+    S::Ok: AsRef<[u8]>,
+{
+    type Item = Result<S::Ok, S::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            match ready!(this.inner.as_mut().try_poll_next(cx)) {
+                Some(Ok(ref frame))
+                    if *this.policy == EmptyFramePolicy::Skip && frame.as_ref().is_empty() => {}
+                item => return Poll::Ready(item),
+            }
+        }
+    }
+}
+
+impl<S, Item> Sink<Item> for EmptyFrames<S>
+where
+    S: Sink<Item>,
+{
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        self.project().inner.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
@@ -0,0 +1,741 @@
+#[cfg(feature = "json")]
+use bytes::Bytes;
+
+use std::{error, fmt, io};
+
+#[cfg(feature = "checksum")]
+use crate::ChecksumAlgorithm;
+
+/// Maximum number of bytes of the offending frame kept in a [`JsonError`].
+///
+/// [`JsonError`]: struct.JsonError.html
+#[cfg(feature = "json")]
+const PREFIX_LEN: usize = 256;
+
+/// Error produced by any of the codecs of this crate.
+///
+/// There is one variant per format, or one per direction for formats whose
+/// backend reports encoding and decoding errors as separate types. Variants
+/// of formats behind a Cargo feature are only present with that feature
+/// enabled.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use tokio_serde::Deserializer;
+/// use tokio_serde_codecs::{CodecError, Json};
+///
+/// let frame = BytesMut::from(&b"[1, 2"[..]);
+/// match Pin::new(&mut Json::<Vec<u32>>::new()).deserialize(&frame) {
+///     Err(CodecError::Json(err)) => assert!(err.to_string().contains("[1, 2")),
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum CodecError {
+    /// A value could not be serialized to or deserialized from JSON.
+    #[cfg(feature = "json")]
+    Json(JsonError),
+    /// A value could not be serialized to or deserialized from Avro.
+    ///
+    /// The errors of apache-avro are large, so they are boxed to keep
+    /// `CodecError` small.
+    #[cfg(feature = "avro")]
+    Avro(Box<apache_avro::Error>),
+    /// A value did not match the schema of an [`Avro`] codec.
+    ///
+    /// [`Avro`]: struct.Avro.html
+    #[cfg(feature = "avro")]
+    AvroSchema(Box<apache_avro::Error>),
+    /// A frame could not be decoded from base64.
+    #[cfg(feature = "base64")]
+    Base64(base64::DecodeError),
+    /// A value could not be serialized to or deserialized from bincode.
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::Error),
+    /// A value could not be deserialized from BSON.
+    #[cfg(feature = "bson")]
+    BsonDecode(bson::de::Error),
+    /// A value could not be serialized to BSON.
+    #[cfg(feature = "bson")]
+    BsonEncode(bson::ser::Error),
+    /// A value could not be deserialized from CBOR.
+    #[cfg(feature = "cbor")]
+    CborDecode(ciborium::de::Error<io::Error>),
+    /// A value could not be serialized to CBOR.
+    #[cfg(feature = "cbor")]
+    CborEncode(ciborium::ser::Error<io::Error>),
+    /// A frame did not carry the checksum of its contents.
+    #[cfg(feature = "checksum")]
+    Checksum(ChecksumMismatch),
+    /// A frame could not be compressed or decompressed.
+    #[cfg(feature = "compress")]
+    Compression(io::Error),
+    /// A value could not be deserialized from FlexBuffers.
+    #[cfg(feature = "flexbuffers")]
+    FlexBuffersDecode(flexbuffers::DeserializationError),
+    /// A value could not be serialized to FlexBuffers.
+    #[cfg(feature = "flexbuffers")]
+    FlexBuffersEncode(flexbuffers::SerializationError),
+    /// Reading from or writing to the underlying transport failed, or a
+    /// frame could not be delimited from it.
+    #[cfg(feature = "codec")]
+    Io(io::Error),
+    /// A value could not be deserialized from MessagePack.
+    #[cfg(feature = "messagepack")]
+    MessagePackDecode(rmp_serde::decode::Error),
+    /// A value could not be serialized to MessagePack.
+    #[cfg(feature = "messagepack")]
+    MessagePackEncode(rmp_serde::encode::Error),
+    /// A value could not be serialized to or deserialized from postcard.
+    #[cfg(feature = "postcard")]
+    Postcard(postcard::Error),
+    /// A value could not be deserialized from RON.
+    #[cfg(feature = "ron")]
+    RonDecode(ron::error::SpannedError),
+    /// A value could not be serialized to RON.
+    #[cfg(feature = "ron")]
+    RonEncode(ron::Error),
+    /// A frame did not conform to the JSON Schema of a [`ValidatedJson`].
+    ///
+    /// [`ValidatedJson`]: struct.ValidatedJson.html
+    #[cfg(feature = "validated")]
+    Schema(SchemaViolation),
+    /// A value could not be deserialized from TOML.
+    #[cfg(feature = "toml")]
+    TomlDecode(toml::de::Error),
+    /// A value could not be serialized to TOML.
+    #[cfg(feature = "toml")]
+    TomlEncode(toml::ser::Error),
+    /// A value could not be deserialized from a URL encoded form.
+    #[cfg(feature = "urlencoded")]
+    UrlEncodedDecode(serde_urlencoded::de::Error),
+    /// A value could not be serialized to a URL encoded form.
+    #[cfg(feature = "urlencoded")]
+    UrlEncodedEncode(serde_urlencoded::ser::Error),
+    /// A frame carried a different schema version than expected.
+    Version(VersionMismatch),
+    /// A value could not be serialized to or deserialized from YAML.
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            #[cfg(feature = "json")]
+            CodecError::Json(ref err) => err.fmt(f),
+            #[cfg(feature = "avro")]
+            CodecError::Avro(ref err) => err.fmt(f),
+            #[cfg(feature = "avro")]
+            CodecError::AvroSchema(ref err) => err.fmt(f),
+            #[cfg(feature = "base64")]
+            CodecError::Base64(ref err) => err.fmt(f),
+            #[cfg(feature = "bincode")]
+            CodecError::Bincode(ref err) => err.fmt(f),
+            #[cfg(feature = "bson")]
+            CodecError::BsonDecode(ref err) => err.fmt(f),
+            #[cfg(feature = "bson")]
+            CodecError::BsonEncode(ref err) => err.fmt(f),
+            #[cfg(feature = "cbor")]
+            CodecError::CborDecode(ref err) => err.fmt(f),
+            #[cfg(feature = "cbor")]
+            CodecError::CborEncode(ref err) => err.fmt(f),
+            #[cfg(feature = "checksum")]
+            CodecError::Checksum(ref err) => err.fmt(f),
+            #[cfg(feature = "compress")]
+            CodecError::Compression(ref err) => err.fmt(f),
+            #[cfg(feature = "flexbuffers")]
+            CodecError::FlexBuffersDecode(ref err) => err.fmt(f),
+            #[cfg(feature = "flexbuffers")]
+            CodecError::FlexBuffersEncode(ref err) => err.fmt(f),
+            #[cfg(feature = "codec")]
+            CodecError::Io(ref err) => err.fmt(f),
+            #[cfg(feature = "messagepack")]
+            CodecError::MessagePackDecode(ref err) => err.fmt(f),
+            #[cfg(feature = "messagepack")]
+            CodecError::MessagePackEncode(ref err) => err.fmt(f),
+            #[cfg(feature = "postcard")]
+            CodecError::Postcard(ref err) => err.fmt(f),
+            #[cfg(feature = "ron")]
+            CodecError::RonDecode(ref err) => err.fmt(f),
+            #[cfg(feature = "ron")]
+            CodecError::RonEncode(ref err) => err.fmt(f),
+            #[cfg(feature = "validated")]
+            CodecError::Schema(ref err) => err.fmt(f),
+            #[cfg(feature = "toml")]
+            CodecError::TomlDecode(ref err) => err.fmt(f),
+            #[cfg(feature = "toml")]
+            CodecError::TomlEncode(ref err) => err.fmt(f),
+            #[cfg(feature = "urlencoded")]
+            CodecError::UrlEncodedDecode(ref err) => err.fmt(f),
+            #[cfg(feature = "urlencoded")]
+            CodecError::UrlEncodedEncode(ref err) => err.fmt(f),
+            CodecError::Version(ref err) => err.fmt(f),
+            #[cfg(feature = "yaml")]
+            CodecError::Yaml(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            #[cfg(feature = "json")]
+            CodecError::Json(ref err) => Some(err),
+            #[cfg(feature = "avro")]
+            CodecError::Avro(ref err) => Some(&**err),
+            #[cfg(feature = "avro")]
+            CodecError::AvroSchema(ref err) => Some(&**err),
+            #[cfg(feature = "base64")]
+            CodecError::Base64(ref err) => Some(err),
+            #[cfg(feature = "bincode")]
+            CodecError::Bincode(ref err) => Some(err),
+            #[cfg(feature = "bson")]
+            CodecError::BsonDecode(ref err) => Some(err),
+            #[cfg(feature = "bson")]
+            CodecError::BsonEncode(ref err) => Some(err),
+            #[cfg(feature = "cbor")]
+            CodecError::CborDecode(ref err) => Some(err),
+            #[cfg(feature = "cbor")]
+            CodecError::CborEncode(ref err) => Some(err),
+            #[cfg(feature = "checksum")]
+            CodecError::Checksum(ref err) => Some(err),
+            #[cfg(feature = "compress")]
+            CodecError::Compression(ref err) => Some(err),
+            #[cfg(feature = "flexbuffers")]
+            CodecError::FlexBuffersDecode(ref err) => Some(err),
+            #[cfg(feature = "flexbuffers")]
+            CodecError::FlexBuffersEncode(ref err) => Some(err),
+            #[cfg(feature = "codec")]
+            CodecError::Io(ref err) => Some(err),
+            #[cfg(feature = "messagepack")]
+            CodecError::MessagePackDecode(ref err) => Some(err),
+            #[cfg(feature = "messagepack")]
+            CodecError::MessagePackEncode(ref err) => Some(err),
+            #[cfg(feature = "postcard")]
+            CodecError::Postcard(ref err) => Some(err),
+            #[cfg(feature = "ron")]
+            CodecError::RonDecode(ref err) => Some(err),
+            #[cfg(feature = "ron")]
+            CodecError::RonEncode(ref err) => Some(err),
+            #[cfg(feature = "validated")]
+            CodecError::Schema(ref err) => Some(err),
+            #[cfg(feature = "toml")]
+            CodecError::TomlDecode(ref err) => Some(err),
+            #[cfg(feature = "toml")]
+            CodecError::TomlEncode(ref err) => Some(err),
+            #[cfg(feature = "urlencoded")]
+            CodecError::UrlEncodedDecode(ref err) => Some(err),
+            #[cfg(feature = "urlencoded")]
+            CodecError::UrlEncodedEncode(ref err) => Some(err),
+            CodecError::Version(ref err) => Some(err),
+            #[cfg(feature = "yaml")]
+            CodecError::Yaml(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<CodecError> for io::Error {
+    fn from(err: CodecError) -> Self {
+        match err {
+            #[cfg(feature = "codec")]
+            CodecError::Io(err) => err,
+            err => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+/// Converts a codec error into an `io::Error` of kind `InvalidData`.
+///
+/// A [`CodecError::Io`] is converted back into the `io::Error` it holds, so
+/// that its kind is kept.
+///
+/// This accepts [`CodecError`] as well as any of the backend errors it
+/// wraps, and is handy with `map_err` when composing with APIs built around
+/// `io::Error`. Note that the streams returned by e.g. [`ReadJson`] already
+/// yield `io::Error`s when reading from an `io::Error` based transport, so no
+/// conversion is needed there.
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+///
+/// use tokio_serde_codecs::{into_io_error, CodecError};
+///
+/// fn read_config(frame: &[u8]) -> io::Result<Vec<u32>> {
+///     serde_json::from_slice(frame).map_err(into_io_error)
+/// }
+///
+/// let err = read_config(b"[1,").unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+///
+/// let err: CodecError = tokio_serde_codecs::decode::<u32>(b"x").unwrap_err();
+/// let err = io::Error::from(err);
+/// assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+/// assert!(err.get_ref().unwrap().is::<CodecError>());
+/// ```
+///
+/// [`CodecError`]: enum.CodecError.html
+/// [`CodecError::Io`]: enum.CodecError.html#variant.Io
+/// [`ReadJson`]: type.ReadJson.html
+pub fn into_io_error<E: Into<CodecError>>(err: E) -> io::Error {
+    io::Error::from(err.into())
+}
+
+#[cfg(feature = "json")]
+impl From<JsonError> for CodecError {
+    fn from(err: JsonError) -> Self {
+        CodecError::Json(err)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for CodecError {
+    fn from(err: serde_json::Error) -> Self {
+        CodecError::Json(err.into())
+    }
+}
+
+#[cfg(feature = "avro")]
+impl From<apache_avro::Error> for CodecError {
+    fn from(err: apache_avro::Error) -> Self {
+        CodecError::Avro(Box::new(err))
+    }
+}
+
+#[cfg(feature = "base64")]
+impl From<base64::DecodeError> for CodecError {
+    fn from(err: base64::DecodeError) -> Self {
+        CodecError::Base64(err)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl From<bincode::Error> for CodecError {
+    fn from(err: bincode::Error) -> Self {
+        CodecError::Bincode(err)
+    }
+}
+
+#[cfg(feature = "bson")]
+impl From<bson::de::Error> for CodecError {
+    fn from(err: bson::de::Error) -> Self {
+        CodecError::BsonDecode(err)
+    }
+}
+
+#[cfg(feature = "bson")]
+impl From<bson::ser::Error> for CodecError {
+    fn from(err: bson::ser::Error) -> Self {
+        CodecError::BsonEncode(err)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl From<ciborium::de::Error<io::Error>> for CodecError {
+    fn from(err: ciborium::de::Error<io::Error>) -> Self {
+        CodecError::CborDecode(err)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl From<ciborium::ser::Error<io::Error>> for CodecError {
+    fn from(err: ciborium::ser::Error<io::Error>) -> Self {
+        CodecError::CborEncode(err)
+    }
+}
+
+#[cfg(feature = "checksum")]
+impl From<ChecksumMismatch> for CodecError {
+    fn from(err: ChecksumMismatch) -> Self {
+        CodecError::Checksum(err)
+    }
+}
+
+#[cfg(feature = "flexbuffers")]
+impl From<flexbuffers::DeserializationError> for CodecError {
+    fn from(err: flexbuffers::DeserializationError) -> Self {
+        CodecError::FlexBuffersDecode(err)
+    }
+}
+
+#[cfg(feature = "flexbuffers")]
+impl From<flexbuffers::SerializationError> for CodecError {
+    fn from(err: flexbuffers::SerializationError) -> Self {
+        CodecError::FlexBuffersEncode(err)
+    }
+}
+
+#[cfg(feature = "codec")]
+impl From<io::Error> for CodecError {
+    fn from(err: io::Error) -> Self {
+        CodecError::Io(err)
+    }
+}
+
+#[cfg(feature = "messagepack")]
+impl From<rmp_serde::decode::Error> for CodecError {
+    fn from(err: rmp_serde::decode::Error) -> Self {
+        CodecError::MessagePackDecode(err)
+    }
+}
+
+#[cfg(feature = "messagepack")]
+impl From<rmp_serde::encode::Error> for CodecError {
+    fn from(err: rmp_serde::encode::Error) -> Self {
+        CodecError::MessagePackEncode(err)
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl From<postcard::Error> for CodecError {
+    fn from(err: postcard::Error) -> Self {
+        CodecError::Postcard(err)
+    }
+}
+
+#[cfg(feature = "ron")]
+impl From<ron::error::SpannedError> for CodecError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        CodecError::RonDecode(err)
+    }
+}
+
+#[cfg(feature = "ron")]
+impl From<ron::Error> for CodecError {
+    fn from(err: ron::Error) -> Self {
+        CodecError::RonEncode(err)
+    }
+}
+
+#[cfg(feature = "validated")]
+impl From<SchemaViolation> for CodecError {
+    fn from(err: SchemaViolation) -> Self {
+        CodecError::Schema(err)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for CodecError {
+    fn from(err: toml::de::Error) -> Self {
+        CodecError::TomlDecode(err)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::ser::Error> for CodecError {
+    fn from(err: toml::ser::Error) -> Self {
+        CodecError::TomlEncode(err)
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl From<serde_urlencoded::de::Error> for CodecError {
+    fn from(err: serde_urlencoded::de::Error) -> Self {
+        CodecError::UrlEncodedDecode(err)
+    }
+}
+
+#[cfg(feature = "urlencoded")]
+impl From<serde_urlencoded::ser::Error> for CodecError {
+    fn from(err: serde_urlencoded::ser::Error) -> Self {
+        CodecError::UrlEncodedEncode(err)
+    }
+}
+
+impl From<VersionMismatch> for CodecError {
+    fn from(err: VersionMismatch) -> Self {
+        CodecError::Version(err)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for CodecError {
+    fn from(err: serde_yaml::Error) -> Self {
+        CodecError::Yaml(err)
+    }
+}
+
+/// Error produced by the JSON codecs.
+///
+/// When a frame fails to deserialize, this captures the length of the frame
+/// and up to its first 256 bytes along with the underlying
+/// `serde_json::Error`, and includes both in the `Display` output. The line
+/// and column reported by serde_json are relative to the frame rather than
+/// to the whole stream.
+///
+/// To keep working with plain `serde_json::Error`s, use [`into_inner`] or
+/// the `From` conversion, which also allows `?` to convert it.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use tokio_serde::Deserializer;
+/// use tokio_serde_codecs::{CodecError, Json};
+///
+/// let frame = BytesMut::from(&br#"{"id":1,"name":oops}"#[..]);
+/// let err = match Pin::new(&mut Json::<serde_json::Value>::new()).deserialize(&frame) {
+///     Err(CodecError::Json(err)) => err,
+///     other => panic!("unexpected result: {:?}", other),
+/// };
+/// assert!(err
+///     .to_string()
+///     .ends_with(r#" (in frame of 20 bytes: "{\"id\":1,\"name\":oops}")"#));
+///
+/// // Only the start of long frames is kept
+/// let frame = BytesMut::from(format!("[{}x]", "1,".repeat(200)).as_bytes());
+/// let err = Pin::new(&mut Json::<Vec<u32>>::new()).deserialize(&frame).unwrap_err();
+/// let message = err.to_string();
+/// assert!(message.contains(r#"(in frame of 403 bytes: "[1,1,1,"#));
+/// assert!(message.ends_with(r#",1,1"...)"#));
+/// ```
+///
+/// [`into_inner`]: #method.into_inner
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub struct JsonError {
+    inner: serde_json::Error,
+    frame: Option<(Bytes, usize)>,
+}
+
+#[cfg(feature = "json")]
+impl JsonError {
+    pub(crate) fn new(inner: serde_json::Error, frame: &[u8]) -> Self {
+        let prefix = &frame[..frame.len().min(PREFIX_LEN)];
+
+        JsonError {
+            inner,
+            frame: Some((Bytes::from(prefix), frame.len())),
+        }
+    }
+
+    /// Returns a reference to the underlying `serde_json::Error`.
+    pub fn get_ref(&self) -> &serde_json::Error {
+        &self.inner
+    }
+
+    /// Consumes the `JsonError`, returning the underlying
+    /// `serde_json::Error`.
+    pub fn into_inner(self) -> serde_json::Error {
+        self.inner
+    }
+
+    /// Returns the first bytes of the frame that failed to deserialize.
+    ///
+    /// This is `None` for errors not caused by a frame, i.e. serialization
+    /// errors.
+    pub fn frame_prefix(&self) -> Option<&[u8]> {
+        self.frame.as_ref().map(|(prefix, _)| &prefix[..])
+    }
+
+    /// Returns the total length of the frame that failed to deserialize.
+    ///
+    /// This is `None` for errors not caused by a frame, i.e. serialization
+    /// errors.
+    pub fn frame_len(&self) -> Option<usize> {
+        self.frame.as_ref().map(|(_, len)| *len)
+    }
+}
+
+#[cfg(feature = "json")]
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)?;
+
+        if let Some((prefix, len)) = &self.frame {
+            write!(
+                f,
+                " (in frame of {} bytes: {:?}",
+                len,
+                String::from_utf8_lossy(prefix)
+            )?;
+
+            if prefix.len() < *len {
+                f.write_str("...")?;
+            }
+
+            f.write_str(")")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+impl error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.inner)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for JsonError {
+    fn from(inner: serde_json::Error) -> Self {
+        JsonError { inner, frame: None }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<JsonError> for serde_json::Error {
+    fn from(err: JsonError) -> Self {
+        err.inner
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<JsonError> for io::Error {
+    fn from(err: JsonError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Error produced by [`Versioned`] when a frame does not carry the expected
+/// schema version.
+///
+/// [`Versioned`]: struct.Versioned.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionMismatch {
+    pub(crate) expected: u8,
+    pub(crate) actual: Option<u8>,
+}
+
+impl VersionMismatch {
+    /// Returns the version the deserializer was configured with.
+    pub fn expected(&self) -> u8 {
+        self.expected
+    }
+
+    /// Returns the version the frame carried.
+    ///
+    /// This is `None` for empty frames, which lack the version byte.
+    pub fn actual(&self) -> Option<u8> {
+        self.actual
+    }
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(
+                f,
+                "schema version mismatch: expected version {}, but frame has version {}",
+                self.expected, actual
+            ),
+            None => write!(
+                f,
+                "schema version mismatch: expected version {}, but frame is empty",
+                self.expected
+            ),
+        }
+    }
+}
+
+impl error::Error for VersionMismatch {}
+
+/// Error produced by [`Checked`] when a frame does not carry the checksum of
+/// its contents.
+///
+/// [`Checked`]: struct.Checked.html
+#[cfg(feature = "checksum")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    pub(crate) algorithm: ChecksumAlgorithm,
+    pub(crate) expected: u32,
+    pub(crate) actual: Option<u32>,
+}
+
+#[cfg(feature = "checksum")]
+impl ChecksumMismatch {
+    /// Returns the algorithm the deserializer was configured with.
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the checksum of the contents of the frame.
+    ///
+    /// For frames too short to carry a checksum, this is the checksum of the
+    /// whole frame.
+    pub fn expected(&self) -> u32 {
+        self.expected
+    }
+
+    /// Returns the checksum the frame carried.
+    ///
+    /// This is `None` for frames too short to carry a checksum.
+    pub fn actual(&self) -> Option<u32> {
+        self.actual
+    }
+}
+
+#[cfg(feature = "checksum")]
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(
+                f,
+                "checksum mismatch: expected {} {:#010x}, but frame carries {:#010x}",
+                self.algorithm.name(),
+                self.expected,
+                actual
+            ),
+            None => write!(
+                f,
+                "checksum mismatch: frame is too short to carry a {} checksum",
+                self.algorithm.name()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "checksum")]
+impl error::Error for ChecksumMismatch {}
+
+/// Error produced by [`ValidatedJson`] when a frame does not conform to its
+/// JSON Schema.
+///
+/// [`ValidatedJson`]: struct.ValidatedJson.html
+#[cfg(feature = "validated")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub(crate) errors: Vec<(String, String)>,
+}
+
+#[cfg(feature = "validated")]
+impl SchemaViolation {
+    /// Returns the locations in the value which failed validation, as JSON
+    /// pointers, together with a description of each failure.
+    ///
+    /// The location of the value itself is the empty string.
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.errors
+            .iter()
+            .map(|(path, message)| (path.as_str(), message.as_str()))
+    }
+}
+
+#[cfg(feature = "validated")]
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("frame does not match the schema")?;
+
+        for (i, (path, message)) in self.errors.iter().enumerate() {
+            let separator = if i == 0 { ": " } else { "; " };
+            // The empty pointer refers to the value as a whole
+            let path = if path.is_empty() { "root" } else { path };
+            write!(f, "{}{} at {}", separator, message, path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "validated")]
+impl error::Error for SchemaViolation {}
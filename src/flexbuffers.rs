@@ -0,0 +1,122 @@
+use bytes::{Bytes, BytesMut};
+use flexbuffers::{DeserializationError, Reader};
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of FlexBuffers encoded buffers to a stream of values.
+pub type ReadFlexBuffers<T, U> = FramedRead<T, U, FlexBuffers<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as
+/// FlexBuffers.
+pub type WriteFlexBuffers<T, U> = FramedWrite<T, U, FlexBuffers<U>>;
+
+/// FlexBuffers serializer and deserializer backed by [flexbuffers].
+///
+/// FlexBuffers is a schemaless binary format whose data can be accessed in
+/// place. Besides deserializing whole values, a frame can be navigated with
+/// the `Reader` returned by [`read_root`], which only looks at the parts of
+/// it that are accessed, e.g. a single field of a large message. Structs are
+/// written as maps keyed by the names of their fields.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::FlexBuffers;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Reading {
+///     sensor: String,
+///     celsius: u32,
+///     samples: Vec<f64>,
+/// }
+///
+/// let reading = Reading {
+///     sensor: "boiler".into(),
+///     celsius: 71,
+///     samples: vec![70.5, 71.25, 71.0],
+/// };
+///
+/// let mut codec = FlexBuffers::<Reading>::new();
+/// let frame = Pin::new(&mut codec).serialize(&reading).unwrap().into();
+///
+/// let root = codec.read_root(&frame).unwrap();
+/// assert_eq!(root.as_map().idx("celsius").as_u32(), 71);
+/// assert_eq!(root.as_map().idx("sensor").as_str(), "boiler");
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame).unwrap(), reading);
+/// ```
+///
+/// [flexbuffers]: https://docs.rs/flexbuffers
+/// [`read_root`]: #method.read_root
+pub struct FlexBuffers<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> FlexBuffers<T> {
+    /// Creates a new FlexBuffers serializer and deserializer.
+    pub fn new() -> Self {
+        FlexBuffers { ghost: PhantomData }
+    }
+
+    /// Returns a reader of the root value of a frame, to access parts of it
+    /// without deserializing the whole value.
+    ///
+    /// Only the header of the root is checked here. Accessing a part of the
+    /// value which is missing or of another type through the `as_*` and
+    /// `idx` methods of the reader yields a default value, such as `0` or an
+    /// empty string, while the `get_*` and `index` methods report an error.
+    pub fn read_root<'a>(&self, src: &'a BytesMut) -> Result<Reader<&'a [u8]>, CodecError> {
+        Reader::get_root(&src[..]).map_err(|err| DeserializationError::from(err).into())
+    }
+}
+
+impl<T> Default for FlexBuffers<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for FlexBuffers<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FlexBuffers<T> {}
+
+impl<T> fmt::Debug for FlexBuffers<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlexBuffers").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for FlexBuffers<T> {
+    const NAME: &'static str = "flexbuffers";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        Ok(flexbuffers::to_vec(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(flexbuffers::from_slice(src)?)
+    }
+}
+
+impl_symmetrical_codec!(FlexBuffers);
@@ -0,0 +1,79 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::CodecError;
+
+/// Format serializing and deserializing values of the same type `T`.
+///
+/// Implementing this and invoking [`impl_symmetrical_codec`] provides the
+/// tokio-serde `Serializer` and `Deserializer` of a codec, including the
+/// instrumentation of every frame, so that a new format only needs to supply
+/// how to encode and decode a single value. The traits of tokio-serde are
+/// foreign to this crate, so they cannot be implemented for every
+/// implementor of this trait at once; the macro implements them for one
+/// codec type at a time instead.
+pub(crate) trait SymmetricalCodec<T> {
+    /// Name of the codec, as reported by the instrumentation.
+    const NAME: &'static str;
+
+    /// Whether frames are written by serde_json, which understands the
+    /// numbers of its `arbitrary_precision` feature.
+    #[cfg(feature = "json")]
+    const JSON: bool = false;
+
+    /// Serializes `item` into a frame.
+    ///
+    /// This accepts any serializable value rather than just a `T`, so that
+    /// a [`DynCodec`] can hand numbers to formats other than JSON in a form
+    /// they understand.
+    ///
+    /// [`DynCodec`]: ../trait.DynCodec.html
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize;
+
+    /// Deserializes a value from the frame `src`.
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>;
+}
+
+/// Implements tokio-serde's `Serializer` and `Deserializer` for a codec
+/// type with a single type parameter, through its `SymmetricalCodec`.
+macro_rules! impl_symmetrical_codec {
+    ($codec:ident) => {
+        impl<T> ::tokio_serde::Deserializer<T> for $codec<T>
+        where
+            for<'a> T: ::serde::Deserialize<'a>,
+        {
+            type Error = $crate::CodecError;
+
+            fn deserialize(
+                self: ::std::pin::Pin<&mut Self>,
+                src: &::bytes::BytesMut,
+            ) -> Result<T, Self::Error> {
+                $crate::instrument::deserialize(
+                    <Self as $crate::format::SymmetricalCodec<T>>::NAME,
+                    src.len(),
+                    || $crate::format::SymmetricalCodec::decode(&*self, src),
+                )
+            }
+        }
+
+        impl<T: ::serde::Serialize> ::tokio_serde::Serializer<T> for $codec<T> {
+            type Error = $crate::CodecError;
+
+            fn serialize(
+                self: ::std::pin::Pin<&mut Self>,
+                item: &T,
+            ) -> Result<::bytes::Bytes, Self::Error> {
+                $crate::instrument::serialize(
+                    <Self as $crate::format::SymmetricalCodec<T>>::NAME,
+                    || $crate::format::SymmetricalCodec::encode(&*self, item),
+                )
+            }
+        }
+    };
+}
+
+pub(crate) use impl_symmetrical_codec;
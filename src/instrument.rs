@@ -0,0 +1,166 @@
+// Hooks run around the serialization and deserialization of every frame, to
+// report them to the instrumentation enabled by Cargo features. Without any
+// of these features, they merely call the closure.
+
+use std::fmt;
+
+/// Target of the spans emitted with the `tracing` feature.
+#[cfg(feature = "tracing")]
+const TARGET: &str = "tokio_serde_codecs";
+
+#[cfg(any(
+    feature = "avro",
+    feature = "bincode",
+    feature = "bson",
+    feature = "cbor",
+    feature = "flexbuffers",
+    feature = "json",
+    feature = "messagepack",
+    feature = "postcard",
+    feature = "ron",
+    feature = "toml",
+    feature = "urlencoded",
+    feature = "yaml"
+))]
+pub(crate) use self::frame::{deserialize, serialize};
+
+/// Hooks run by the codecs, only compiled with a format feature calling them.
+#[cfg(any(
+    feature = "avro",
+    feature = "bincode",
+    feature = "bson",
+    feature = "cbor",
+    feature = "flexbuffers",
+    feature = "json",
+    feature = "messagepack",
+    feature = "postcard",
+    feature = "ron",
+    feature = "toml",
+    feature = "urlencoded",
+    feature = "yaml"
+))]
+mod frame {
+    use bytes::Bytes;
+
+    use std::fmt;
+
+    #[cfg(feature = "tracing")]
+    use super::TARGET;
+
+    /// Runs `f`, deserializing a frame of `len` bytes with `codec`.
+    #[inline]
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn deserialize<T, E, F>(codec: &'static str, len: usize, f: F) -> Result<T, E>
+    where
+        E: fmt::Display,
+        F: FnOnce() -> Result<T, E>,
+    {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            target: TARGET,
+            "deserialize",
+            codec,
+            len,
+            error = tracing::field::Empty,
+        )
+        .entered();
+
+        let result = f();
+
+        #[cfg(feature = "tracing")]
+        {
+            if let Err(ref err) = result {
+                span.record("error", tracing::field::display(err));
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            if result.is_ok() {
+                metrics::counter!("frames_decoded", "codec" => codec).increment(1);
+                metrics::counter!("bytes_decoded", "codec" => codec).increment(len as u64);
+            }
+        }
+
+        result
+    }
+
+    /// Result of serializing a value, whose length in bytes gets reported.
+    #[cfg_attr(not(any(feature = "tracing", feature = "metrics")), allow(dead_code))]
+    pub(crate) trait Serialized {
+        fn len(&self) -> usize;
+    }
+
+    impl Serialized for Bytes {
+        fn len(&self) -> usize {
+            Bytes::len(self)
+        }
+    }
+
+    /// Number of bytes appended to a caller provided buffer.
+    impl Serialized for usize {
+        fn len(&self) -> usize {
+            *self
+        }
+    }
+
+    /// Runs `f`, serializing a value into a frame with `codec`.
+    #[inline]
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn serialize<T, E, F>(codec: &'static str, f: F) -> Result<T, E>
+    where
+        T: Serialized,
+        E: fmt::Display,
+        F: FnOnce() -> Result<T, E>,
+    {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            target: TARGET,
+            "serialize",
+            codec,
+            len = tracing::field::Empty,
+            error = tracing::field::Empty,
+        )
+        .entered();
+
+        let result = f();
+
+        #[cfg(feature = "tracing")]
+        {
+            match result {
+                Ok(ref frame) => span.record("len", frame.len()),
+                Err(ref err) => span.record("error", tracing::field::display(err)),
+            };
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            if let Ok(ref frame) = result {
+                metrics::counter!("frames_encoded", "codec" => codec).increment(1);
+                metrics::counter!("bytes_encoded", "codec" => codec).increment(frame.len() as u64);
+            }
+        }
+
+        result
+    }
+}
+
+/// Reports that a frame of `len` bytes was dropped as it failed to
+/// deserialize with `err`.
+#[inline]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn skipped<E: fmt::Display>(len: usize, err: &E) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        target: TARGET,
+        len,
+        error = %err,
+        "skipping frame which failed to deserialize",
+    );
+}
@@ -0,0 +1,1862 @@
+use bytes::{Bytes, BytesMut};
+use pin_project::pin_project;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::ser::{CompactFormatter, Formatter, PrettyFormatter};
+use tokio_serde::{Deserializer, FramedRead, FramedWrite, Serializer};
+
+use std::{convert::TryFrom, fmt, io, marker::PhantomData, pin::Pin};
+
+use crate::{
+    format::SymmetricalCodec,
+    instrument,
+    limits::{Limited, Limits},
+    number, sort, CodecError, JsonError, NumberPolicy,
+};
+
+/// Initial capacity of the buffer a frame is serialized into, the same as
+/// the one of `serde_json::to_vec`, so that small messages are written
+/// without growing it.
+const INITIAL_CAPACITY: usize = 128;
+
+/// Adapts a stream of JSON encoded buffers to a stream of values.
+pub type ReadJson<T, U> = FramedRead<T, U, SymmetricalJson<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as JSON.
+pub type WriteJson<T, U> = FramedWrite<T, U, SymmetricalJson<U>>;
+
+/// JSON serializer and deserializer using the same type in both directions.
+///
+/// Since [`FramedRead`] forwards `Sink` calls and [`FramedWrite`] forwards
+/// `Stream` calls to the wrapped transport, a duplex transport of a single
+/// message type can be built by nesting a [`ReadJson`] inside a
+/// [`WriteJson`]. See the `echo` example for a full program doing this.
+///
+/// [`ReadJson`]: type.ReadJson.html
+/// [`WriteJson`]: type.WriteJson.html
+/// [`FramedRead`]: https://docs.rs/tokio-serde/0.4/tokio_serde/struct.FramedRead.html
+/// [`FramedWrite`]: https://docs.rs/tokio-serde/0.4/tokio_serde/struct.FramedWrite.html
+pub type SymmetricalJson<T> = Json<T, T>;
+
+/// JSON serializer and deserializer passing values through untouched.
+///
+/// Deserializing only checks that a frame holds a valid JSON value, and
+/// hands back its bytes as a `RawValue`, which is then serialized verbatim.
+/// This suits e.g. proxies which forward most of the messages they receive
+/// without looking into them, and keeps their formatting intact. Whitespace
+/// around the value is not part of it, though.
+///
+/// Struct fields of type `Box<RawValue>` may be used in the same way with
+/// [`Json`], to only parse part of a message, e.g. a large payload which is
+/// rarely looked into. All options of `Json` work with such fields, but the
+/// limits on elements and string lengths don't apply inside them, as their
+/// contents are not turned into values. [`Json::sort_keys`] sorts the
+/// objects within them as well.
+///
+/// This requires the `raw-value` feature. simd-json cannot produce
+/// `RawValue`s, so with the `simd` feature such frames are read by
+/// serde_json once simd-json has failed on them.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::RawJson;
+///
+/// let frame = BytesMut::from(&b"{ \"a\" :  [1,\n  2.50] }"[..]);
+///
+/// let mut json = RawJson::new();
+/// let value = Pin::new(&mut json).deserialize(&frame).unwrap();
+/// assert_eq!(Pin::new(&mut json).serialize(&value).unwrap(), frame);
+/// ```
+///
+/// Deferring the parsing of a single field:
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use serde::{Deserialize, Serialize};
+/// use serde_json::value::RawValue;
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Json;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Message {
+///     id: u32,
+///     payload: Box<RawValue>,
+/// }
+///
+/// let frame = BytesMut::from(&br#"{"id":7,"payload":{ "readings" : [1.50, 2] }}"#[..]);
+///
+/// let mut json = Json::<Message>::new();
+/// let message = Pin::new(&mut json).deserialize(&frame).unwrap();
+/// assert_eq!(message.id, 7);
+/// assert_eq!(message.payload.get(), r#"{ "readings" : [1.50, 2] }"#);
+///
+/// assert_eq!(Pin::new(&mut json).serialize(&message).unwrap(), frame);
+/// ```
+///
+/// [`Json::sort_keys`]: struct.Json.html#method.sort_keys
+///
+/// [`Json`]: struct.Json.html
+#[cfg(feature = "raw-value")]
+pub type RawJson = SymmetricalJson<Box<serde_json::value::RawValue>>;
+
+/// JSON serializer and deserializer backed by [serde_json].
+///
+/// Values read from the transport are deserialized as `Item`, while values
+/// written to it are serialized from `SinkItem`. This allows e.g. a client to
+/// send requests and receive responses of a different type over the same
+/// transport. `SinkItem` defaults to `Item`.
+///
+/// Values are serialized straight into the buffer of the frame, without an
+/// intermediate `Vec`. Still, a `serde_json::Value` is turned into text from
+/// scratch every time it is written, even if it was read from a frame and
+/// left untouched, and reading one allocates for each of its strings, arrays
+/// and objects. Messages which are only forwarded, or parts of them, are
+/// passed through far more cheaply as `RawValue`s, which keep the bytes they
+/// were read from and write them back verbatim; see [`RawJson`] and the
+/// `passthrough` example.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Json;
+///
+/// let mut json = Json::<i32>::default();
+///
+/// let buf = Pin::new(&mut json).serialize(&42).unwrap();
+/// assert_eq!(buf, &b"42"[..]);
+///
+/// let value = Pin::new(&mut Json::<i32>::new()).deserialize(&buf.into()).unwrap();
+/// assert_eq!(value, 42);
+/// ```
+///
+/// Frames are parsed in place, which gives the same values as reading them
+/// through serde_json's `io::Read` based deserializer:
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use serde_json::{json, Value};
+/// use tokio_serde::Deserializer;
+/// use tokio_serde_codecs::Json;
+///
+/// let rows: Vec<Value> = (0..1000)
+///     .map(|i| json!({ "id": i, "cells": [[i, i * 2], [i as f64 / 4.0], ["x"]] }))
+///     .collect();
+/// let value = json!({ "rows": rows });
+///
+/// let frame = BytesMut::from(serde_json::to_vec(&value).unwrap());
+/// let decoded = Pin::new(&mut Json::<Value>::new()).deserialize(&frame).unwrap();
+///
+/// let read: Value = serde_json::from_reader(&frame[..]).unwrap();
+/// assert_eq!(decoded, read);
+/// assert_eq!(decoded, value);
+/// ```
+///
+/// [serde_json]: https://docs.rs/serde_json
+/// [`RawJson`]: type.RawJson.html
+pub struct Json<Item, SinkItem = Item> {
+    max_depth: Option<usize>,
+    max_len: Option<usize>,
+    limits: Limits,
+    allow_trailing: bool,
+    number_policy: NumberPolicy,
+    strip_bom: bool,
+    sort_keys: bool,
+    trailing_newline: bool,
+    ascii_only: bool,
+    ghost: PhantomData<(Item, SinkItem)>,
+}
+
+impl<Item, SinkItem> Json<Item, SinkItem> {
+    /// Creates a new JSON serializer and deserializer.
+    ///
+    /// # Examples
+    ///
+    /// The codec implements `Debug` whatever the types of its items, and
+    /// shows its settings:
+    ///
+    /// ```
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// struct Opaque;
+    ///
+    /// #[derive(Debug)]
+    /// struct Transport {
+    ///     codec: Json<Opaque>,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("{:?}", Json::<String>::new()),
+    ///     "Json { max_depth: None, max_len: None, max_elements: None, \
+    ///      max_string_len: None, allow_trailing: false, number_policy: Reject, \
+    ///      strip_bom: true, sort_keys: false, trailing_newline: false, \
+    ///      ascii_only: false }",
+    /// );
+    /// let transport = Transport { codec: Json::new() };
+    /// assert!(format!("{:?}", transport).starts_with("Transport { codec: Json {"));
+    /// ```
+    pub fn new() -> Self {
+        Json {
+            max_depth: None,
+            max_len: None,
+            limits: Limits::default(),
+            allow_trailing: false,
+            number_policy: NumberPolicy::Reject,
+            strip_bom: true,
+            sort_keys: false,
+            trailing_newline: false,
+            ascii_only: false,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets the maximum nesting depth of arrays and objects accepted when
+    /// deserializing.
+    ///
+    /// Frames nested deeper than this are rejected by a quick scan before
+    /// they are parsed, which protects against peers trying to exhaust the
+    /// stack. By default only serde_json's built-in limit of 128 applies,
+    /// which also stays in effect when a larger depth is set here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use serde_json::Value;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::{CodecError, Json};
+    ///
+    /// let mut json = Json::<Value>::new().max_depth(32);
+    ///
+    /// let frame = BytesMut::from(format!("{}1{}", "[".repeat(32), "]".repeat(32)).as_bytes());
+    /// assert!(Pin::new(&mut json).deserialize(&frame).is_ok());
+    ///
+    /// // Far too deep to be parsed recursively, and not even closed
+    /// let frame = BytesMut::from("[".repeat(10_000).as_bytes());
+    /// match Pin::new(&mut json).deserialize(&frame) {
+    ///     Err(CodecError::Json(err)) => {
+    ///         assert!(err.to_string().starts_with("recursion limit exceeded"));
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Sets the maximum length in bytes of frames accepted when
+    /// deserializing.
+    ///
+    /// Longer frames are rejected without being parsed. There is no limit by
+    /// default, as the framing layer usually caps the frame length already.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use serde_json::Value;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::{CodecError, Json};
+    ///
+    /// let mut json = Json::<Value>::new().max_len(8);
+    ///
+    /// let frame = BytesMut::from(&b"[1,2,3]"[..]);
+    /// assert!(Pin::new(&mut json).deserialize(&frame).is_ok());
+    ///
+    /// // Not even valid JSON, but the length is checked first
+    /// let frame = BytesMut::from(&b"[1,2,3,garbage]"[..]);
+    /// match Pin::new(&mut json).deserialize(&frame) {
+    ///     Err(CodecError::Json(err)) => {
+    ///         assert!(err.to_string().starts_with("frame exceeds maximum length of 8 bytes"));
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Sets the maximum number of elements of an array, or members of an
+    /// object, accepted when deserializing.
+    ///
+    /// The elements are counted while the frame is parsed, so that a frame
+    /// is rejected as soon as any array or object in it grows beyond the
+    /// limit, before the rest of it is deserialized. This bounds the size of
+    /// the structures built by the deserialized value, which [`max_len`]
+    /// alone does not tightly do, e.g. `[0,0,0,...]` holds one element every
+    /// two bytes. Elements skipped by the target type, such as unknown
+    /// fields of a struct, are not counted. There is no limit by default.
+    ///
+    /// Like [`max_string_len`], this is checked by a proxy around serde_json
+    /// and is not supported by the `simd` parser, so frames are always
+    /// parsed with serde_json when a limit is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let mut json = Json::<serde_json::Value>::new().max_elements(3);
+    ///
+    /// let frame = BytesMut::from(&b"{\"a\":[1,2,3],\"b\":{}}"[..]);
+    /// assert!(Pin::new(&mut json).deserialize(&frame).is_ok());
+    ///
+    /// let frame = BytesMut::from(&b"{\"a\":[1,2,3,4],\"b\":{}}"[..]);
+    /// let err = Pin::new(&mut json).deserialize(&frame).unwrap_err();
+    /// assert!(err.to_string().starts_with("array or object exceeds maximum of 3 elements"));
+    /// ```
+    ///
+    /// [`max_len`]: #method.max_len
+    /// [`max_string_len`]: #method.max_string_len
+    pub fn max_elements(mut self, max_elements: usize) -> Self {
+        self.limits.max_elements = Some(max_elements);
+        self
+    }
+
+    /// Sets the maximum length in bytes of strings accepted when
+    /// deserializing.
+    ///
+    /// The limit applies to strings and object keys alike, and to their
+    /// length after escape sequences have been decoded. A frame holding a
+    /// longer string is rejected without the string being handed to the
+    /// target type. There is no limit by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let mut json = Json::<Vec<String>>::new().max_string_len(4);
+    ///
+    /// let frame = BytesMut::from(&b"[\"abcd\"]"[..]);
+    /// assert!(Pin::new(&mut json).deserialize(&frame).is_ok());
+    ///
+    /// let frame = BytesMut::from(&b"[\"abcde\"]"[..]);
+    /// let err = Pin::new(&mut json).deserialize(&frame).unwrap_err();
+    /// assert!(err.to_string().starts_with("string exceeds maximum length of 4 bytes"));
+    /// ```
+    pub fn max_string_len(mut self, max_string_len: usize) -> Self {
+        self.limits.max_string_len = Some(max_string_len);
+        self
+    }
+
+    /// Sets whether data following the JSON value of a frame is accepted
+    /// when deserializing.
+    ///
+    /// By default deserialization is strict: only whitespace may follow the
+    /// value, so that a frame like `{"a":1}garbage` is reported as an error
+    /// instead of the garbage being silently dropped. When trailing data is
+    /// allowed, everything after the first value is ignored. Permissive
+    /// parsing always uses serde_json, even when the `simd` feature is
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let frame = BytesMut::from(&b"[1,2]garbage"[..]);
+    ///
+    /// let mut strict = Json::<Vec<i32>>::new();
+    /// assert!(Pin::new(&mut strict).deserialize(&frame).is_err());
+    ///
+    /// let mut permissive = Json::<Vec<i32>>::new().allow_trailing(true);
+    /// let value = Pin::new(&mut permissive).deserialize(&frame).unwrap();
+    /// assert_eq!(value, vec![1, 2]);
+    /// ```
+    pub fn allow_trailing(mut self, allow_trailing: bool) -> Self {
+        self.allow_trailing = allow_trailing;
+        self
+    }
+
+    /// Sets how floats which are NaN or infinite are handled.
+    ///
+    /// By default, serializing such a float fails and the non-standard
+    /// literals for them are rejected, as required by RFC 8259. Unlike for
+    /// [`NumberPolicy::Reject`] and [`NumberPolicy::Null`], deserializing with
+    /// [`NumberPolicy::Literal`] always uses serde_json, even when the `simd`
+    /// feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use tokio_serde::{Deserializer, Serializer};
+    /// use tokio_serde_codecs::{Json, NumberPolicy};
+    ///
+    /// let mut strict = Json::<f64>::new();
+    /// assert!(Pin::new(&mut strict).serialize(&f64::NAN).is_err());
+    ///
+    /// let mut null = Json::<Option<f64>>::new().number_policy(NumberPolicy::Null);
+    /// let frame = Pin::new(&mut null).serialize(&Some(f64::NAN)).unwrap();
+    /// assert_eq!(frame, &b"null"[..]);
+    /// assert_eq!(Pin::new(&mut null).deserialize(&frame.into()).unwrap(), None);
+    ///
+    /// let mut literal = Json::<Vec<f64>>::new().number_policy(NumberPolicy::Literal);
+    /// let frame = Pin::new(&mut literal).serialize(&vec![f64::NAN, f64::NEG_INFINITY]).unwrap();
+    /// assert_eq!(frame, &b"[NaN,-Infinity]"[..]);
+    /// let values = Pin::new(&mut literal).deserialize(&frame.into()).unwrap();
+    /// assert!(values[0].is_nan());
+    /// assert_eq!(values[1], f64::NEG_INFINITY);
+    /// ```
+    ///
+    /// [`NumberPolicy::Reject`]: enum.NumberPolicy.html#variant.Reject
+    /// [`NumberPolicy::Null`]: enum.NumberPolicy.html#variant.Null
+    /// [`NumberPolicy::Literal`]: enum.NumberPolicy.html#variant.Literal
+    pub fn number_policy(mut self, number_policy: NumberPolicy) -> Self {
+        self.number_policy = number_policy;
+        self
+    }
+
+    /// Sets whether a UTF-8 byte order mark at the start of a frame is
+    /// skipped when deserializing.
+    ///
+    /// Some producers, mostly on Windows, prepend the byte order mark
+    /// `EF BB BF` to the JSON they write, which serde_json rejects. By
+    /// default a single leading mark is removed before parsing. Bytes which
+    /// look like a mark anywhere else in the frame, e.g. inside of strings,
+    /// are left alone. When disabled, frames starting with a mark fail to
+    /// deserialize.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let frame = BytesMut::from(&b"\xEF\xBB\xBF{\"a\":1}"[..]);
+    ///
+    /// let mut json = Json::<serde_json::Value>::new();
+    /// let value = Pin::new(&mut json).deserialize(&frame).unwrap();
+    /// assert_eq!(value, serde_json::json!({ "a": 1 }));
+    ///
+    /// let frame = BytesMut::from(&b"{\"a\":1}"[..]);
+    /// let value = Pin::new(&mut json).deserialize(&frame).unwrap();
+    /// assert_eq!(value, serde_json::json!({ "a": 1 }));
+    ///
+    /// let frame = BytesMut::from("\u{FEFF}\"\u{FEFF}\"".as_bytes());
+    /// let value = Pin::new(&mut json).deserialize(&frame).unwrap();
+    /// assert_eq!(value, "\u{FEFF}");
+    ///
+    /// let mut strict = Json::<serde_json::Value>::new().strip_bom(false);
+    /// assert!(Pin::new(&mut strict).deserialize(&frame).is_err());
+    /// ```
+    pub fn strip_bom(mut self, strip_bom: bool) -> Self {
+        self.strip_bom = strip_bom;
+        self
+    }
+
+    /// Sets whether the keys of objects are sorted when serializing.
+    ///
+    /// By default, the fields of structs are written in the order they are
+    /// declared in, and the entries of maps in the order the map yields them,
+    /// which for e.g. a `HashMap` differs between runs. Sorting the keys
+    /// makes the output deterministic, so that snapshots of the same data
+    /// can be compared or diffed. Keys are compared by their text, and the
+    /// output is not canonicalized otherwise; see [`CanonicalJson`] for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use serde::Serialize;
+    /// use tokio_serde::Serializer;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     name: &'static str,
+    ///     address: &'static str,
+    ///     limits: Limits,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Limits {
+    ///     memory: u32,
+    ///     cpu: u32,
+    /// }
+    ///
+    /// let config = Config {
+    ///     name: "db",
+    ///     address: "10.0.0.1",
+    ///     limits: Limits { memory: 512, cpu: 2 },
+    /// };
+    ///
+    /// let mut json = Json::<(), Config>::new().sort_keys(true);
+    /// let frame = Pin::new(&mut json).serialize(&config).unwrap();
+    /// assert_eq!(
+    ///     frame,
+    ///     &br#"{"address":"10.0.0.1","limits":{"cpu":2,"memory":512},"name":"db"}"#[..],
+    /// );
+    /// ```
+    ///
+    /// [`CanonicalJson`]: struct.CanonicalJson.html
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Sets whether a newline is written after each serialized value.
+    ///
+    /// Compact JSON never contains a newline of its own, so with this
+    /// enabled every frame is exactly one line. This keeps records apart
+    /// when frames are written to a file or a pipe one after another, for
+    /// processing them line by line later on. By default nothing follows the
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use tokio_serde::Serializer;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let mut json = Json::<(), _>::new().trailing_newline(true);
+    /// let frame = Pin::new(&mut json)
+    ///     .serialize(&serde_json::json!(["two\nlines", [1, 2]]))
+    ///     .unwrap();
+    /// assert_eq!(frame, &b"[\"two\\nlines\",[1,2]]\n"[..]);
+    /// assert_eq!(frame.iter().filter(|&&b| b == b'\n').count(), 1);
+    /// ```
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Sets whether all non-ASCII characters are written as `\u` escapes.
+    ///
+    /// By default, strings are written as UTF-8, escaping only the characters
+    /// JSON requires to be escaped. With this enabled, the output is pure
+    /// ASCII, e.g. for transports which mangle multibyte characters.
+    /// Characters outside the Basic Multilingual Plane, such as most emoji,
+    /// are written as UTF-16 surrogate pairs. Deserializing understands
+    /// escapes either way, so peers read the same strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use tokio_serde::{Deserializer, Serializer};
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let mut json = Json::<String>::new().ascii_only(true);
+    /// let text = "Grüße 🦀".to_owned();
+    ///
+    /// let frame = Pin::new(&mut json).serialize(&text).unwrap();
+    /// assert!(frame.is_ascii());
+    /// assert_eq!(frame, &br#""Gr\u00fc\u00dfe \ud83e\udd80""#[..]);
+    ///
+    /// assert_eq!(Pin::new(&mut json).deserialize(&frame.into()).unwrap(), text);
+    /// ```
+    pub fn ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    /// Serializes `item`, appending it to `dst` instead of returning a new
+    /// frame.
+    ///
+    /// This writes exactly what `serialize` produces, without allocating a
+    /// buffer of its own, which suits e.g. writers batching several values
+    /// into one buffer. Nothing is appended if serializing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let json = Json::<(), i32>::new();
+    ///
+    /// let mut dst = BytesMut::new();
+    /// for value in &[1, 2, 3] {
+    ///     json.serialize_into(value, &mut dst).unwrap();
+    ///     dst.extend_from_slice(b"\n");
+    /// }
+    ///
+    /// let values = serde_json::Deserializer::from_slice(&dst)
+    ///     .into_iter::<i32>()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(values, vec![1, 2, 3]);
+    /// ```
+    pub fn serialize_into(&self, item: &SinkItem, dst: &mut BytesMut) -> Result<(), CodecError>
+    where
+        SinkItem: Serialize,
+    {
+        instrument::serialize("json", || self.write(item, dst)).map(drop)
+    }
+
+    /// Serializes all of `items` into a single frame holding a JSON array.
+    ///
+    /// This lets a writer batch many small values to save the framing
+    /// overhead of each of them. The frame is read back with
+    /// [`deserialize_array`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let json = Json::<u32>::new();
+    /// let items: Vec<u32> = (0..100).collect();
+    ///
+    /// let frame = json.serialize_many(&items).unwrap();
+    /// assert!(frame.starts_with(b"[0,1,2,"));
+    ///
+    /// assert_eq!(json.deserialize_array(&frame).unwrap(), items);
+    /// ```
+    ///
+    /// [`deserialize_array`]: #method.deserialize_array
+    pub fn serialize_many(&self, items: &[SinkItem]) -> Result<Bytes, CodecError>
+    where
+        SinkItem: Serialize,
+    {
+        instrument::serialize("json", || {
+            let mut buf = BytesMut::new();
+            self.write(items, &mut buf)?;
+            Ok(buf.freeze())
+        })
+    }
+
+    /// Serializes `item` into `dst`, returning the number of bytes written.
+    ///
+    /// This writes exactly what `serialize` produces, without allocating,
+    /// unless keys are to be sorted. It suits small messages written into a
+    /// buffer on the stack before being copied into a frame.
+    ///
+    /// If the value does not fit into `dst`, this fails with a [`JsonError`]
+    /// whose underlying `serde_json::Error` is an I/O error of kind
+    /// `WriteZero`, which tells it apart from the value itself failing to
+    /// serialize. In either case the contents of `dst` are unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    ///
+    /// use tokio_serde_codecs::{CodecError, Json};
+    ///
+    /// let json = Json::<(), Vec<u32>>::new();
+    /// let mut buf = [0; 16];
+    ///
+    /// let len = json.serialize_into_slice(&vec![1, 2, 3], &mut buf).unwrap();
+    /// assert_eq!(&buf[..len], b"[1,2,3]");
+    ///
+    /// match json.serialize_into_slice(&vec![1; 10], &mut buf) {
+    ///     Err(CodecError::Json(err)) => {
+    ///         assert_eq!(err.get_ref().io_error_kind(), Some(io::ErrorKind::WriteZero));
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    ///
+    /// [`JsonError`]: struct.JsonError.html
+    pub fn serialize_into_slice(&self, item: &SinkItem, dst: &mut [u8]) -> Result<usize, CodecError>
+    where
+        SinkItem: Serialize,
+    {
+        instrument::serialize("json", || {
+            let capacity = dst.len();
+            let mut writer = &mut dst[..];
+            number::to_writer(&mut writer, item, self.number_policy, self.ascii_only)?;
+            let len = capacity - writer.len();
+
+            if self.sort_keys {
+                // Sorting only rearranges the members, keeping the length
+                let sorted = sort::sort_keys(&dst[..len]);
+                dst[..len].copy_from_slice(&sorted);
+            }
+
+            if self.trailing_newline {
+                match dst.get_mut(len) {
+                    Some(b) => *b = b'\n',
+                    None => {
+                        return Err(serde_json::Error::io(io::ErrorKind::WriteZero.into()).into())
+                    }
+                }
+                return Ok(len + 1);
+            }
+
+            Ok(len)
+        })
+    }
+
+    /// Deserializes every value of a frame holding several concatenated JSON
+    /// values.
+    ///
+    /// The values may be separated by whitespace, e.g. `{"a":1} {"a":2}`.
+    /// This is different from a frame holding a single JSON array, which is
+    /// read by [`deserialize_array`] instead. The options of the codec apply
+    /// to every value as with `deserialize`, except that data following a
+    /// value is the next value rather than being rejected. The whole frame is
+    /// parsed up front, stopping after the first error, and always with
+    /// serde_json even when the `simd` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use serde::Deserialize;
+    /// use tokio_serde_codecs::{CodecError, Json, NumberPolicy};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Sample {
+    ///     id: u32,
+    ///     values: Vec<f64>,
+    /// }
+    ///
+    /// let frame = BytesMut::from(&br#"{"id":1,"values":[0.5]} {"id":2,"values":[NaN,2,3]}"#[..]);
+    ///
+    /// let json = Json::<Sample>::new().number_policy(NumberPolicy::Literal);
+    /// let samples = json
+    ///     .deserialize_many(&frame)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(samples.len(), 2);
+    /// assert_eq!(samples[0], Sample { id: 1, values: vec![0.5] });
+    /// assert!(samples[1].values[0].is_nan());
+    ///
+    /// // Limits apply to each of the values
+    /// let json = Json::<Sample>::new()
+    ///     .number_policy(NumberPolicy::Literal)
+    ///     .max_elements(2);
+    /// let mut samples = json.deserialize_many(&frame);
+    /// assert_eq!(samples.next().unwrap().unwrap().id, 1);
+    /// match samples.next() {
+    ///     Some(Err(CodecError::Json(_))) => {}
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// assert!(samples.next().is_none());
+    /// ```
+    ///
+    /// [`deserialize_array`]: #method.deserialize_array
+    pub fn deserialize_many<'a>(
+        &self,
+        src: &'a BytesMut,
+    ) -> impl Iterator<Item = Result<Item, CodecError>> + 'a
+    where
+        for<'de> Item: Deserialize<'de> + 'a,
+    {
+        let src = self.frame(src);
+        if let Err(err) = self.check_limits(src) {
+            return vec![Err(err)].into_iter();
+        }
+
+        let values = if self.number_policy == NumberPolicy::Literal {
+            number::from_slice_literal_many(src, self.limits)
+        } else {
+            let mut deserializer = serde_json::Deserializer::from_slice(src);
+            deserialize_each(&mut deserializer, |deserializer| {
+                Item::deserialize(Limited::new(deserializer, self.limits))
+            })
+        };
+
+        values
+            .into_iter()
+            .map(|value| value.map_err(|err| JsonError::new(err, src).into()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Deserializes a value from a frame that is already known to be valid
+    /// UTF-8.
+    ///
+    /// This behaves exactly like `deserialize`, but spares copying text
+    /// received from e.g. a WebSocket into a `BytesMut` first. With the
+    /// default options, the text is also parsed with `serde_json::from_str`,
+    /// which skips validating the UTF-8 of strings again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// let text = r#"{"text":"\u00e9t\u00e9","id":7}"#;
+    ///
+    /// let mut json = Json::<serde_json::Value>::new();
+    /// let value = json.deserialize_str(text).unwrap();
+    /// assert_eq!(value, serde_json::json!({ "text": "été", "id": 7 }));
+    ///
+    /// let frame = BytesMut::from(text.as_bytes());
+    /// assert_eq!(Pin::new(&mut json).deserialize(&frame).unwrap(), value);
+    /// ```
+    pub fn deserialize_str(&self, src: &str) -> Result<Item, CodecError>
+    where
+        for<'de> Item: Deserialize<'de>,
+    {
+        instrument::deserialize("json", src.len(), || {
+            let src = match src.strip_prefix('\u{FEFF}') {
+                Some(rest) if self.strip_bom => rest,
+                _ => src,
+            };
+
+            if cfg!(all(feature = "simd", not(feature = "arbitrary-precision")))
+                || self.number_policy == NumberPolicy::Literal
+                || self.allow_trailing
+                || !self.limits.is_unlimited()
+            {
+                return self.parse(src.as_bytes());
+            }
+
+            self.check_limits(src.as_bytes())?;
+            serde_json::from_str(src).map_err(|err| JsonError::new(err, src.as_bytes()).into())
+        })
+    }
+
+    /// Deserializes every element of a frame holding a single JSON array, as
+    /// written by [`serialize_many`].
+    ///
+    /// Unlike [`deserialize_many`], which reads values following each other,
+    /// this expects the values to be enclosed in `[` and `]` and separated by
+    /// commas. All options apply to the frame as a whole, so e.g. the elements
+    /// of the array count towards [`max_elements`].
+    ///
+    /// [`serialize_many`]: #method.serialize_many
+    /// [`deserialize_many`]: #method.deserialize_many
+    /// [`max_elements`]: #method.max_elements
+    pub fn deserialize_array(&self, src: &[u8]) -> Result<Vec<Item>, CodecError>
+    where
+        for<'de> Item: Deserialize<'de>,
+    {
+        instrument::deserialize("json", src.len(), || self.parse(self.frame(src)))
+    }
+
+    pub(crate) fn deserialize_slice(&self, src: &[u8]) -> Result<Item, CodecError>
+    where
+        for<'de> Item: Deserialize<'de>,
+    {
+        instrument::deserialize("json", src.len(), || self.parse(self.frame(src)))
+    }
+
+    /// Parses a frame without its byte order mark.
+    fn parse<T>(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'de> T: Deserialize<'de>,
+    {
+        self.check_limits(src)?;
+
+        if self.number_policy == NumberPolicy::Literal {
+            number::from_slice_literal(src, self.allow_trailing, self.limits)
+                .map_err(|err| JsonError::new(err, src).into())
+        } else if self.allow_trailing || !self.limits.is_unlimited() {
+            from_slice_limited(src, self.allow_trailing, self.limits)
+        } else {
+            from_slice(src)
+        }
+    }
+
+    /// Appends `item` to `dst`, returning the number of bytes written.
+    pub(crate) fn write<T>(&self, item: &T, dst: &mut BytesMut) -> Result<usize, CodecError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let start = dst.len();
+
+        let result = number::to_writer(BytesWriter(dst), item, self.number_policy, self.ascii_only);
+        if let Err(err) = result {
+            // Don't leave a partially written value behind
+            dst.truncate(start);
+            return Err(err.into());
+        }
+
+        if self.sort_keys {
+            let sorted = sort::sort_keys(&dst[start..]);
+            dst.truncate(start);
+            dst.extend_from_slice(&sorted);
+        }
+
+        if self.trailing_newline {
+            dst.extend_from_slice(b"\n");
+        }
+
+        Ok(dst.len() - start)
+    }
+
+    /// Returns the part of `src` to parse, without the byte order mark if
+    /// it is to be stripped.
+    fn frame<'a>(&self, src: &'a [u8]) -> &'a [u8] {
+        const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+        if self.strip_bom && src.starts_with(BOM) {
+            &src[BOM.len()..]
+        } else {
+            src
+        }
+    }
+
+    fn check_limits(&self, src: &[u8]) -> Result<(), CodecError> {
+        if let Some(max_len) = self.max_len {
+            if src.len() > max_len {
+                let err = serde::de::Error::custom(format_args!(
+                    "frame exceeds maximum length of {} bytes",
+                    max_len
+                ));
+                return Err(JsonError::new(err, src).into());
+            }
+        }
+
+        if let Some(max_depth) = self.max_depth {
+            if exceeds_depth(src, max_depth) {
+                let err = serde::de::Error::custom("recursion limit exceeded");
+                return Err(JsonError::new(err, src).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<Item: DeserializeOwned, SinkItem> Json<Item, SinkItem> {
+    /// Creates a new JSON serializer and deserializer, checking that `Item`
+    /// owns all of its data.
+    ///
+    /// This behaves exactly like [`new`]. Deserializing requires an `Item`
+    /// which does not borrow from the frame, and a borrowing type usually
+    /// only fails that requirement far away from the mistake, e.g. when the
+    /// value is read from a [`ReadJson`] stream, with a hard to read error
+    /// about `for<'a> Deserialize<'a>`. Creating the deserializer with this
+    /// constructor reports such a type right away, at the line naming it.
+    /// To deserialize borrowing types, see
+    /// [`borrow_deserialize`].
+    ///
+    /// # Examples
+    ///
+    /// ```compile_fail
+    /// use tokio_serde_codecs::Json;
+    ///
+    /// // error: implementation of `Deserialize` is not general enough
+    /// let json = Json::<&str>::owned();
+    /// ```
+    ///
+    /// [`new`]: #method.new
+    /// [`ReadJson`]: type.ReadJson.html
+    /// [`borrow_deserialize`]: fn.borrow_deserialize.html
+    pub fn owned() -> Self {
+        Self::new()
+    }
+}
+
+impl<Item, SinkItem> Default for Json<Item, SinkItem> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Item, SinkItem> Clone for Json<Item, SinkItem> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Item, SinkItem> Copy for Json<Item, SinkItem> {}
+
+impl<Item, SinkItem> fmt::Debug for Json<Item, SinkItem> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Json")
+            .field("max_depth", &self.max_depth)
+            .field("max_len", &self.max_len)
+            .field("max_elements", &self.limits.max_elements)
+            .field("max_string_len", &self.limits.max_string_len)
+            .field("allow_trailing", &self.allow_trailing)
+            .field("number_policy", &self.number_policy)
+            .field("strip_bom", &self.strip_bom)
+            .field("sort_keys", &self.sort_keys)
+            .field("trailing_newline", &self.trailing_newline)
+            .field("ascii_only", &self.ascii_only)
+            .finish()
+    }
+}
+
+impl<Item, SinkItem> Deserializer<Item> for Json<Item, SinkItem>
+where
+    for<'a> Item: Deserialize<'a>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Item, Self::Error> {
+        self.deserialize_slice(src)
+    }
+}
+
+impl<Item, SinkItem: Serialize> Serializer<SinkItem> for Json<Item, SinkItem> {
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &SinkItem) -> Result<Bytes, Self::Error> {
+        let mut buf = BytesMut::with_capacity(INITIAL_CAPACITY);
+        self.serialize_into(item, &mut buf)?;
+        Ok(buf.freeze())
+    }
+}
+
+// The tokio-serde impls above are written out rather than generated by
+// `impl_symmetrical_codec`, as `Json` may read and write different types.
+impl<T> SymmetricalCodec<T> for Json<T, T> {
+    const NAME: &'static str = "json";
+    const JSON: bool = true;
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        let mut buf = BytesMut::with_capacity(INITIAL_CAPACITY);
+        self.write(item, &mut buf)?;
+        Ok(buf.freeze())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        self.parse(self.frame(src))
+    }
+}
+
+/// Deserializes a single JSON value from `src`, exactly like the
+/// deserializer of a default [`Json`] does.
+///
+/// This is handy for values which don't arrive through a stream in the
+/// first place, e.g. the body of an HTTP request, or for testing message
+/// types. A leading byte order mark is skipped, and data following the value
+/// is rejected. To use other options, call `deserialize` on a configured
+/// `Json` instead.
+///
+/// # Examples
+///
+/// ```
+/// let value: Vec<u32> = tokio_serde_codecs::decode(b"\xEF\xBB\xBF[1, 2]").unwrap();
+/// assert_eq!(value, vec![1, 2]);
+///
+/// assert!(tokio_serde_codecs::decode::<Vec<u32>>(b"[1, 2] [3]").is_err());
+/// ```
+///
+/// [`Json`]: struct.Json.html
+pub fn decode<T: DeserializeOwned>(src: &[u8]) -> Result<T, CodecError> {
+    Json::<T>::new().deserialize_slice(src)
+}
+
+/// Serializes `item` as a single JSON value, exactly like the serializer of
+/// a default [`Json`] does.
+///
+/// This is the counterpart of [`decode`].
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("a", vec![1.5]);
+///
+/// let frame = tokio_serde_codecs::encode(&map).unwrap();
+/// assert_eq!(frame, &br#"{"a":[1.5]}"#[..]);
+///
+/// let value: BTreeMap<String, Vec<f64>> = tokio_serde_codecs::decode(&frame).unwrap();
+/// assert_eq!(value["a"], vec![1.5]);
+///
+/// // Like `Json`, non-finite floats are rejected
+/// assert!(tokio_serde_codecs::encode(&f64::NAN).is_err());
+/// ```
+///
+/// [`Json`]: struct.Json.html
+/// [`decode`]: fn.decode.html
+pub fn encode<T: Serialize>(item: &T) -> Result<Bytes, CodecError> {
+    let mut buf = BytesMut::new();
+    Json::<(), T>::new().serialize_into(item, &mut buf)?;
+    Ok(buf.freeze())
+}
+
+/// Value decoded from JSON through `TryFrom`.
+///
+/// This lets call sites holding a byte slice or `Bytes` convert it with the
+/// standard conversion traits, with the same result as [`decode`]. The
+/// decoded value is the field of the wrapper.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+///
+/// use bytes::Bytes;
+/// use tokio_serde_codecs::JsonDecode;
+///
+/// let JsonDecode(value) = JsonDecode::<Vec<u32>>::try_from(&b"[1, 2]"[..]).unwrap();
+/// assert_eq!(value, vec![1, 2]);
+///
+/// let frame = Bytes::from(&b"{\"id\":7}"[..]);
+/// let value: serde_json::Value = JsonDecode::try_from(frame).unwrap().into_inner();
+/// assert_eq!(value["id"], 7);
+///
+/// assert!(JsonDecode::<Vec<u32>>::try_from(&b"[1, "[..]).is_err());
+/// ```
+///
+/// [`decode`]: fn.decode.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JsonDecode<T>(pub T);
+
+impl<T> JsonDecode<T> {
+    /// Consumes the wrapper, returning the decoded value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned> TryFrom<&[u8]> for JsonDecode<T> {
+    type Error = CodecError;
+
+    fn try_from(src: &[u8]) -> Result<Self, Self::Error> {
+        decode(src).map(JsonDecode)
+    }
+}
+
+impl<T: DeserializeOwned> TryFrom<Bytes> for JsonDecode<T> {
+    type Error = CodecError;
+
+    fn try_from(src: Bytes) -> Result<Self, Self::Error> {
+        decode(&src).map(JsonDecode)
+    }
+}
+
+/// JSON serializer and deserializer writing compact output through
+/// [`JsonWith`].
+///
+/// The output is identical to the one of [`Json`].
+///
+/// [`Json`]: struct.Json.html
+/// [`JsonWith`]: struct.JsonWith.html
+pub type CompactJson<T> = JsonWith<T, CompactFormatter>;
+
+/// JSON serializer and deserializer writing output through a custom
+/// `serde_json` [`Formatter`].
+///
+/// The formatter controls how the output is laid out, e.g. whitespace,
+/// separators or how strings are escaped. It is cloned for every value that
+/// is serialized. Deserialization behaves exactly like a default [`Json`],
+/// skipping a leading byte order mark. Frames are decoded without limits and
+/// with [`NumberPolicy::Reject`], so use a configured `Json` as the
+/// deserializer to change these.
+///
+/// For indented output, see [`PrettyJson`].
+///
+/// # Examples
+///
+/// Escaping control characters with uppercase hex digits, as some peers
+/// compare frames byte for byte against the output of other encoders:
+///
+/// ```
+/// use std::{io, pin::Pin};
+///
+/// use serde_json::ser::{CharEscape, Formatter};
+/// use tokio_serde::Serializer;
+/// use tokio_serde_codecs::JsonWith;
+///
+/// #[derive(Clone, Debug)]
+/// struct UppercaseEscapes;
+///
+/// impl Formatter for UppercaseEscapes {
+///     fn write_char_escape<W>(&mut self, writer: &mut W, escape: CharEscape) -> io::Result<()>
+///     where
+///         W: ?Sized + io::Write,
+///     {
+///         match escape {
+///             CharEscape::AsciiControl(byte) => write!(writer, "\\u{:04X}", byte),
+///             escape => serde_json::ser::CompactFormatter.write_char_escape(writer, escape),
+///         }
+///     }
+/// }
+///
+/// let mut codec = JsonWith::<String, _>::new(UppercaseEscapes);
+///
+/// let frame = Pin::new(&mut codec).serialize(&"\x1b[0m\n".to_owned()).unwrap();
+/// assert_eq!(frame, &br#""\u001B[0m\n""#[..]);
+///
+/// assert_eq!(
+///     format!("{:?}", codec),
+///     "JsonWith { formatter: UppercaseEscapes }",
+/// );
+/// ```
+///
+/// [`Formatter`]: https://docs.rs/serde_json/1/serde_json/ser/trait.Formatter.html
+/// [`Json`]: struct.Json.html
+/// [`NumberPolicy::Reject`]: enum.NumberPolicy.html#variant.Reject
+/// [`PrettyJson`]: struct.PrettyJson.html
+pub struct JsonWith<T, F> {
+    formatter: F,
+    ghost: PhantomData<T>,
+}
+
+impl<T, F> JsonWith<T, F> {
+    /// Creates a new JSON serializer and deserializer using `formatter`.
+    pub fn new(formatter: F) -> Self {
+        JsonWith {
+            formatter,
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<T, F: Default> Default for JsonWith<T, F> {
+    fn default() -> Self {
+        Self::new(F::default())
+    }
+}
+
+impl<T, F: Clone> Clone for JsonWith<T, F> {
+    fn clone(&self) -> Self {
+        Self::new(self.formatter.clone())
+    }
+}
+
+impl<T, F: fmt::Debug> fmt::Debug for JsonWith<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonWith")
+            .field("formatter", &self.formatter)
+            .finish()
+    }
+}
+
+impl<T, F> Deserializer<T> for JsonWith<T, F>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        decode(src)
+    }
+}
+
+impl<T, F> Serializer<T> for JsonWith<T, F>
+where
+    T: Serialize,
+    F: Formatter + Clone,
+{
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        instrument::serialize("json", || {
+            let mut buf = BytesMut::new();
+            let formatter = self.formatter.clone();
+            let mut serializer =
+                serde_json::Serializer::with_formatter(BytesWriter(&mut buf), formatter);
+            item.serialize(&mut serializer)?;
+            Ok(buf.freeze())
+        })
+    }
+}
+
+/// Deserializes a JSON value borrowing from the frame it was read from.
+///
+/// The [`Deserializer`] trait can only produce values that own their data,
+/// which means strings are always copied out of the frame. This function
+/// instead ties the lifetime of the returned value to `src`, so fields such
+/// as `&'a str` point directly into the frame without copying.
+///
+/// Because the value borrows from `src`, the frame must be kept alive for as
+/// long as the value is in use. This rules out the [`ReadJson`] stream, which
+/// drops every frame right after deserializing it. Instead, call this on the
+/// frames of the underlying stream directly.
+///
+/// Strings containing escape sequences cannot be borrowed as `&str`, as
+/// their decoded form does not appear in the frame, and fail to deserialize.
+/// To handle those as well, use `Cow<'a, str>` fields marked with
+/// `#[serde(borrow)]`: they borrow from the frame in the common case, and
+/// only allocate for strings which contain escapes. Without the attribute,
+/// serde always deserializes a `Cow` as owned.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use bytes::BytesMut;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Message<'a> {
+///     #[serde(borrow)]
+///     name: Cow<'a, str>,
+/// }
+///
+/// let frame = BytesMut::from(&br#"{"name":"plain"}"#[..]);
+/// let message: Message = tokio_serde_codecs::borrow_deserialize(&frame).unwrap();
+/// assert!(matches!(message.name, Cow::Borrowed("plain")));
+///
+/// let frame = BytesMut::from(&br#"{"name":"with \"escapes\""}"#[..]);
+/// let message: Message = tokio_serde_codecs::borrow_deserialize(&frame).unwrap();
+/// assert!(matches!(message.name, Cow::Owned(_)));
+/// assert_eq!(message.name, r#"with "escapes""#);
+/// ```
+///
+/// Fields of type `&str` borrow from the frame as well, but fail to
+/// deserialize strings with escapes:
+///
+/// ```
+/// use bytes::BytesMut;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Message<'a> {
+///     id: u32,
+///     name: &'a str,
+/// }
+///
+/// let frame = BytesMut::from(&br#"{"id":1,"name":"plain"}"#[..]);
+/// let message: Message = tokio_serde_codecs::borrow_deserialize(&frame).unwrap();
+/// assert_eq!(message.id, 1);
+/// assert_eq!(message.name, "plain");
+/// assert!(frame.as_ptr_range().contains(&message.name.as_ptr()));
+///
+/// let frame = BytesMut::from(&br#"{"id":2,"name":"tab\there"}"#[..]);
+/// assert!(tokio_serde_codecs::borrow_deserialize::<Message>(&frame).is_err());
+/// ```
+///
+/// [`Deserializer`]: https://docs.rs/tokio-serde/0.4/tokio_serde/trait.Deserializer.html
+/// [`ReadJson`]: type.ReadJson.html
+pub fn borrow_deserialize<'a, T>(src: &'a BytesMut) -> Result<T, serde_json::Error>
+where
+    T: Deserialize<'a>,
+{
+    serde_json::from_slice(src)
+}
+
+/// JSON serializer producing indented, human readable output.
+///
+/// Deserialization behaves exactly like a default [`Json`]. As with `Json`,
+/// each value spans multiple lines, so frames must still be delimited by
+/// some other means such as a length header.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use bytes::BytesMut;
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::PrettyJson;
+///
+/// let mut codec = PrettyJson::<Vec<u32>>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&vec![1, 2]).unwrap();
+/// assert_eq!(frame, &b"[\n  1,\n  2\n]"[..]);
+///
+/// // Like `Json`, frames starting with a byte order mark are accepted
+/// let frame = BytesMut::from(&b"\xEF\xBB\xBF[1, 2]"[..]);
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame).unwrap(), [1, 2]);
+/// ```
+///
+/// [`Json`]: struct.Json.html
+pub struct PrettyJson<T> {
+    indent: Vec<u8>,
+    sort_keys: bool,
+    trailing_newline: bool,
+    ghost: PhantomData<T>,
+}
+
+impl<T> PrettyJson<T> {
+    /// Creates a new pretty JSON serializer indenting with two spaces, the
+    /// same as `serde_json::to_vec_pretty`.
+    pub fn new() -> Self {
+        Self::with_indent(2)
+    }
+
+    /// Creates a new pretty JSON serializer indenting with `indent` spaces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use serde_json::json;
+    /// use tokio_serde::Serializer;
+    /// use tokio_serde_codecs::PrettyJson;
+    ///
+    /// let mut codec = PrettyJson::with_indent(4);
+    ///
+    /// let value = json!({ "id": 1, "tags": ["a"] });
+    /// let frame = Pin::new(&mut codec).serialize(&value).unwrap();
+    /// assert_eq!(
+    ///     std::str::from_utf8(&frame).unwrap(),
+    ///     "{\n    \"id\": 1,\n    \"tags\": [\n        \"a\"\n    ]\n}",
+    /// );
+    /// ```
+    pub fn with_indent(indent: usize) -> Self {
+        PrettyJson {
+            indent: vec![b' '; indent],
+            sort_keys: false,
+            trailing_newline: false,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets whether the keys of objects are sorted when serializing.
+    ///
+    /// This works the same as [`Json::sort_keys`], and keeps the indentation
+    /// intact.
+    ///
+    /// [`Json::sort_keys`]: struct.Json.html#method.sort_keys
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Sets whether a newline is written after each serialized value.
+    ///
+    /// serde_json ends pretty output with the closing bracket of the value,
+    /// so values written one after another, e.g. to a log file, run into
+    /// each other. By default nothing follows the value.
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    fn write(&self, item: &T) -> Result<Bytes, CodecError>
+    where
+        T: Serialize,
+    {
+        let mut buf = Vec::new();
+        let formatter = PrettyFormatter::with_indent(&self.indent);
+        let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+        item.serialize(&mut serializer)?;
+
+        if self.sort_keys {
+            buf = sort::sort_keys(&buf);
+        }
+
+        if self.trailing_newline {
+            buf.push(b'\n');
+        }
+
+        Ok(buf.into())
+    }
+}
+
+impl<T> Default for PrettyJson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for PrettyJson<T> {
+    fn clone(&self) -> Self {
+        PrettyJson {
+            indent: self.indent.clone(),
+            sort_keys: self.sort_keys,
+            trailing_newline: self.trailing_newline,
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for PrettyJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrettyJson")
+            .field("indent", &self.indent.len())
+            .field("sort_keys", &self.sort_keys)
+            .field("trailing_newline", &self.trailing_newline)
+            .finish()
+    }
+}
+
+impl<T> Deserializer<T> for PrettyJson<T>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        decode(src)
+    }
+}
+
+impl<T: Serialize> Serializer<T> for PrettyJson<T> {
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        instrument::serialize("json", || self.write(item))
+    }
+}
+
+/// Value to be serialized by [`FormattedJson`], together with whether to
+/// indent it.
+///
+/// [`FormattedJson`]: struct.FormattedJson.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Formatted<T> {
+    /// The value to serialize.
+    pub value: T,
+    /// Whether to write the value indented, like [`PrettyJson`], rather than
+    /// compact, like [`Json`].
+    ///
+    /// [`PrettyJson`]: struct.PrettyJson.html
+    /// [`Json`]: struct.Json.html
+    pub pretty: bool,
+}
+
+impl<T> Formatted<T> {
+    /// Wraps a value to be written compact.
+    pub fn compact(value: T) -> Self {
+        Formatted {
+            value,
+            pretty: false,
+        }
+    }
+
+    /// Wraps a value to be written indented.
+    pub fn pretty(value: T) -> Self {
+        Formatted {
+            value,
+            pretty: true,
+        }
+    }
+}
+
+impl<T> From<T> for Formatted<T> {
+    fn from(value: T) -> Self {
+        Formatted::compact(value)
+    }
+}
+
+/// JSON serializer choosing between compact and indented output for every
+/// value.
+///
+/// Values are written as [`Formatted`] items, whose `pretty` flag selects
+/// the output of [`PrettyJson`] or the compact one of [`Json`]. This lets
+/// a single sink send mostly compact messages, but the occasional one meant
+/// to be read by humans, e.g. a status dump on a debug connection, indented.
+/// Deserialization behaves exactly like a default [`Json`], yielding plain
+/// values.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde_json::json;
+/// use tokio_serde::Serializer;
+/// use tokio_serde_codecs::{Formatted, FormattedJson};
+///
+/// let mut json = FormattedJson::new();
+/// let status = json!({ "uptime": 120, "connections": [1, 2] });
+///
+/// let frame = Pin::new(&mut json).serialize(&Formatted::compact(status.clone())).unwrap();
+/// assert!(!frame.contains(&b'\n'));
+///
+/// let frame = Pin::new(&mut json).serialize(&Formatted::pretty(status)).unwrap();
+/// assert!(frame.contains(&b'\n'));
+/// ```
+///
+/// [`Formatted`]: struct.Formatted.html
+/// [`PrettyJson`]: struct.PrettyJson.html
+/// [`Json`]: struct.Json.html
+pub struct FormattedJson<T> {
+    pretty: PrettyJson<T>,
+}
+
+impl<T> FormattedJson<T> {
+    /// Creates a new JSON serializer indenting pretty values with two spaces.
+    pub fn new() -> Self {
+        Self::with_pretty(PrettyJson::new())
+    }
+
+    /// Creates a new JSON serializer writing pretty values like `pretty`.
+    pub fn with_pretty(pretty: PrettyJson<T>) -> Self {
+        FormattedJson { pretty }
+    }
+}
+
+impl<T> Default for FormattedJson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for FormattedJson<T> {
+    fn clone(&self) -> Self {
+        Self::with_pretty(self.pretty.clone())
+    }
+}
+
+impl<T> fmt::Debug for FormattedJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FormattedJson")
+            .field("pretty", &self.pretty)
+            .finish()
+    }
+}
+
+impl<T> Deserializer<T> for FormattedJson<T>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        decode(src)
+    }
+}
+
+impl<T: Serialize> Serializer<Formatted<T>> for FormattedJson<T> {
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &Formatted<T>) -> Result<Bytes, Self::Error> {
+        instrument::serialize("json", || {
+            if item.pretty {
+                self.pretty.write(&item.value)
+            } else {
+                Ok(serde_json::to_vec(&item.value)?.into())
+            }
+        })
+    }
+}
+
+/// JSON serializer reusing an internal buffer across calls.
+///
+/// Serialized values are written into a scratch buffer owned by the
+/// serializer, and each frame is split off from it. Once the frames handed
+/// out earlier have been dropped, their memory is reclaimed for the following
+/// values, which saves an allocation per frame when sending many messages.
+/// Deserialization behaves exactly like a default [`Json`].
+///
+/// [`Json`]: struct.Json.html
+#[pin_project]
+pub struct BufferedJson<T> {
+    buf: BytesMut,
+    capacity: usize,
+    ghost: PhantomData<T>,
+}
+
+impl<T> BufferedJson<T> {
+    /// Creates a new buffered JSON serializer and deserializer.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a new buffered JSON serializer and deserializer which reserves
+    /// room for at least `capacity` bytes before serializing each value.
+    ///
+    /// Without a capacity, the buffer grows step by step while a value is
+    /// written, reallocating several times for large values. Choosing a
+    /// capacity a bit above the size of a typical serialized message lets
+    /// most values be written with a single allocation, or none at all once
+    /// earlier frames have been dropped. Values larger than the capacity are
+    /// still serialized, growing the buffer as needed.
+    pub fn with_capacity(capacity: usize) -> Self {
+        BufferedJson {
+            buf: BytesMut::with_capacity(capacity),
+            capacity,
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for BufferedJson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for BufferedJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferedJson")
+            .field("capacity", &self.buf.capacity())
+            .finish()
+    }
+}
+
+impl<T> Deserializer<T> for BufferedJson<T>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        decode(src)
+    }
+}
+
+impl<T: Serialize> Serializer<T> for BufferedJson<T> {
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        instrument::serialize("json", || {
+            let this = self.project();
+            let buf = this.buf;
+            buf.reserve(*this.capacity);
+
+            if let Err(err) = serde_json::to_writer(BytesWriter(buf), item) {
+                // Don't let a partially written value leak into the next frame
+                buf.clear();
+                return Err(err.into());
+            }
+
+            Ok(buf.take().freeze())
+        })
+    }
+}
+
+/// Parses the single JSON value held by a frame.
+#[cfg(any(not(feature = "simd"), feature = "arbitrary-precision"))]
+pub(crate) fn from_slice<T>(src: &[u8]) -> Result<T, CodecError>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    serde_json::from_slice(src).map_err(|err| JsonError::new(err, src).into())
+}
+
+/// Parses the single JSON value held by a frame using simd-json.
+///
+/// simd-json parses in place, so the frame is copied into a scratch buffer
+/// first. If it fails, the frame is parsed again with serde_json, so that
+/// errors are the same as without the feature, and values simd-json cannot
+/// produce, e.g. ones with `RawValue` fields, are still read.
+///
+/// simd-json knows nothing of serde_json's `arbitrary_precision`, so with
+/// that feature serde_json always parses the frame instead.
+#[cfg(all(feature = "simd", not(feature = "arbitrary-precision")))]
+pub(crate) fn from_slice<T>(src: &[u8]) -> Result<T, CodecError>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    let mut scratch = src.to_vec();
+    simd_json::serde::from_slice(&mut scratch)
+        .or_else(|_| serde_json::from_slice(src).map_err(|err| JsonError::new(err, src).into()))
+}
+
+/// Parses the JSON value of a frame using serde_json, enforcing `limits`
+/// and optionally ignoring any data after the value.
+fn from_slice_limited<T>(src: &[u8], allow_trailing: bool, limits: Limits) -> Result<T, CodecError>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    let mut deserializer = serde_json::Deserializer::from_slice(src);
+
+    let result = T::deserialize(Limited::new(&mut deserializer, limits)).and_then(|value| {
+        if !allow_trailing {
+            deserializer.end()?;
+        }
+        Ok(value)
+    });
+
+    result.map_err(|err| JsonError::new(err, src).into())
+}
+
+/// Deserializes the values following each other in the input of
+/// `deserializer` with `deserialize`, until the input ends or a value fails
+/// to deserialize.
+pub(crate) fn deserialize_each<'de, R, T, F>(
+    deserializer: &mut serde_json::Deserializer<R>,
+    mut deserialize: F,
+) -> Vec<Result<T, serde_json::Error>>
+where
+    R: serde_json::de::Read<'de>,
+    F: FnMut(&mut serde_json::Deserializer<R>) -> Result<T, serde_json::Error>,
+{
+    let mut values = Vec::new();
+
+    // `end` skips the whitespace before the next value, and only succeeds
+    // once nothing but whitespace is left
+    while deserializer.end().is_err() {
+        let value = deserialize(deserializer);
+        let failed = value.is_err();
+        values.push(value);
+
+        if failed {
+            break;
+        }
+    }
+
+    values
+}
+
+/// Returns whether arrays and objects in `src` are nested deeper than
+/// `max_depth`.
+///
+/// Brackets inside strings are skipped, but the input is not validated
+/// otherwise; that is left to the parser.
+fn exceeds_depth(src: &[u8], max_depth: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &b in src {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+        } else {
+            match b {
+                b'"' => in_string = true,
+                b'[' | b'{' => {
+                    depth += 1;
+                    if depth > max_depth {
+                        return true;
+                    }
+                }
+                b']' | b'}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+
+    false
+}
+
+/// `io::Write` adaptor appending to a `BytesMut`.
+///
+/// Unlike `bytes::buf::Writer`, the buffer is grown as needed instead of the
+/// output being cut short once its capacity runs out.
+pub(crate) struct BytesWriter<'a>(pub(crate) &'a mut BytesMut);
+
+impl io::Write for BytesWriter<'_> {
+    fn write(&mut self, src: &[u8]) -> io::Result<usize> {
+        self.0.extend_from_slice(src);
+        Ok(src.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use bytes::BytesMut;
+use serde::{Deserialize, Serialize};
+use tokio_codec::{Decoder, Encoder, FramedRead, FramedWrite, LengthDelimitedCodec};
+use tokio_io::{AsyncRead, AsyncWrite};
+
+use std::{fmt, io, marker::PhantomData};
+
+use crate::{Json, ReadJson, WriteJson};
+
+/// Creates a stream of JSON values read from `io`, with frames delimited by
+/// a length header.
+///
+/// This is a shorthand for wrapping `io` in a `FramedRead` with a default
+/// `LengthDelimitedCodec`, and that again in a [`ReadJson`]. Use
+/// [`read_json_from_io_with`] to tune the framing, e.g. the maximum frame
+/// length.
+///
+/// [`ReadJson`]: type.ReadJson.html
+/// [`read_json_from_io_with`]: fn.read_json_from_io_with.html
+pub fn read_json_from_io<R: AsyncRead, T>(
+    io: R,
+) -> ReadJson<FramedRead<R, LengthDelimitedCodec>, T> {
+    read_json_from_io_with(io, LengthDelimitedCodec::new())
+}
+
+/// Creates a stream of JSON values read from `io`, with frames delimited by
+/// the given `LengthDelimitedCodec`.
+///
+/// By default, `LengthDelimitedCodec` rejects frames longer than 8 MB. To
+/// exchange larger values, raise the limit with `set_max_frame_length` on
+/// the codecs of both the reading and the [writing] side, as the writer
+/// refuses to send frames over its limit as well.
+///
+/// # Examples
+///
+/// ```
+/// use futures::{executor::block_on, SinkExt, TryStreamExt};
+/// use tokio_codec::LengthDelimitedCodec;
+/// use tokio_serde_codecs::{read_json_from_io, read_json_from_io_with, write_json_from_io_with};
+///
+/// fn framing() -> LengthDelimitedCodec {
+///     let mut codec = LengthDelimitedCodec::new();
+///     codec.set_max_frame_length(16 * 1024 * 1024);
+///     codec
+/// }
+///
+/// // Larger than the default limit of 8 MB
+/// let message = "x".repeat(10 * 1024 * 1024);
+///
+/// block_on(async {
+///     let mut buf = Vec::new();
+///     let mut writer = write_json_from_io_with::<_, String>(&mut buf, framing());
+///     writer.send(message.clone()).await.unwrap();
+///     drop(writer);
+///
+///     let mut reader = read_json_from_io_with::<_, String>(&buf[..], framing());
+///     assert_eq!(reader.try_next().await.unwrap(), Some(message));
+///
+///     // The default framing refuses the frame
+///     let mut reader = read_json_from_io::<_, String>(&buf[..]);
+///     assert!(reader.try_next().await.is_err());
+/// });
+/// ```
+///
+/// [writing]: fn.write_json_from_io_with.html
+pub fn read_json_from_io_with<R: AsyncRead, T>(
+    io: R,
+    codec: LengthDelimitedCodec,
+) -> ReadJson<FramedRead<R, LengthDelimitedCodec>, T> {
+    ReadJson::new(FramedRead::new(io, codec), Json::new())
+}
+
+/// Creates a sink of JSON values written to `io`, with frames delimited by a
+/// length header.
+///
+/// This is a shorthand for wrapping `io` in a `FramedWrite` with a default
+/// `LengthDelimitedCodec`, and that again in a [`WriteJson`]. Use
+/// [`write_json_from_io_with`] to tune the framing.
+///
+/// [`WriteJson`]: type.WriteJson.html
+/// [`write_json_from_io_with`]: fn.write_json_from_io_with.html
+pub fn write_json_from_io<W: AsyncWrite, T>(
+    io: W,
+) -> WriteJson<FramedWrite<W, LengthDelimitedCodec>, T> {
+    write_json_from_io_with(io, LengthDelimitedCodec::new())
+}
+
+/// Creates a sink of JSON values written to `io`, with frames delimited by
+/// the given `LengthDelimitedCodec`.
+///
+/// Values whose JSON is longer than the maximum frame length of the codec,
+/// 8 MB by default, fail to be sent. See [`read_json_from_io_with`] for how
+/// to raise the limit.
+///
+/// # Examples
+///
+/// ```
+/// use futures::{executor::block_on, SinkExt, TryStreamExt};
+/// use tokio_codec::LengthDelimitedCodec;
+/// use tokio_serde_codecs::{read_json_from_io, write_json_from_io_with};
+///
+/// let mut framing = LengthDelimitedCodec::new();
+/// framing.set_max_frame_length(8);
+///
+/// block_on(async {
+///     let mut buf = Vec::new();
+///     let mut writer = write_json_from_io_with::<_, Vec<u32>>(&mut buf, framing);
+///     writer.send(vec![1, 2, 3]).await.unwrap();
+///
+///     // `[1,2,3,4]` is longer than 8 bytes
+///     assert!(writer.send(vec![1, 2, 3, 4]).await.is_err());
+///     drop(writer);
+///
+///     // Only the frame which fit was written, with a 4 byte header
+///     assert_eq!(buf, b"\0\0\0\x07[1,2,3]");
+///
+///     let mut reader = read_json_from_io::<_, Vec<u32>>(&buf[..]);
+///     assert_eq!(reader.try_next().await.unwrap(), Some(vec![1, 2, 3]));
+///     assert_eq!(reader.try_next().await.unwrap(), None);
+/// });
+/// ```
+///
+/// [`read_json_from_io_with`]: fn.read_json_from_io_with.html
+pub fn write_json_from_io_with<W: AsyncWrite, T>(
+    io: W,
+    codec: LengthDelimitedCodec,
+) -> WriteJson<FramedWrite<W, LengthDelimitedCodec>, T> {
+    WriteJson::new(FramedWrite::new(io, codec), Json::new())
+}
+
+/// Codec for length delimited JSON.
+///
+/// This is a `Decoder`/`Encoder` of values, so that a plain `Framed` yields
+/// a stream and sink of `T` without any tokio-serde adaptor on top. The wire
+/// format is the same as when combining a default `LengthDelimitedCodec`
+/// with [`Json`]: every value is written as a 4 byte big-endian length
+/// header followed by that many bytes of compact JSON. The length does not
+/// include the header itself.
+///
+/// # Examples
+///
+/// Exchanging values between the two ends of a socket pair:
+///
+/// ```
+/// use futures::{SinkExt, TryStreamExt};
+/// use serde::{Deserialize, Serialize};
+/// use tokio::{codec::Framed, net::UnixStream};
+/// use tokio_serde_codecs::JsonCodec;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Ping {
+///     seq: u32,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (client, server) = UnixStream::pair().unwrap();
+///     let mut client = Framed::new(client, JsonCodec::<Ping>::new());
+///     let mut server = Framed::new(server, JsonCodec::<Ping>::new());
+///
+///     client.send(Ping { seq: 1 }).await.unwrap();
+///     let ping = server.try_next().await.unwrap().unwrap();
+///     assert_eq!(ping, Ping { seq: 1 });
+///
+///     server.send(Ping { seq: ping.seq + 1 }).await.unwrap();
+///     assert_eq!(client.try_next().await.unwrap(), Some(Ping { seq: 2 }));
+///
+///     // Closing one end ends the stream of the other
+///     drop(client);
+///     assert_eq!(server.try_next().await.unwrap(), None);
+/// }
+/// ```
+///
+/// [`Json`]: struct.Json.html
+pub struct JsonCodec<T> {
+    framing: LengthDelimitedCodec,
+    ghost: PhantomData<T>,
+}
+
+impl<T> JsonCodec<T> {
+    /// Creates a new length delimited JSON codec.
+    pub fn new() -> Self {
+        JsonCodec {
+            framing: LengthDelimitedCodec::new(),
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length in bytes of the JSON value of a frame.
+    ///
+    /// Longer frames fail to decode, and longer values fail to encode. The
+    /// default is 8 MB, as for `LengthDelimitedCodec`.
+    pub fn max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.framing.set_max_frame_length(max_frame_length);
+        self
+    }
+}
+
+impl<T> Default for JsonCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for JsonCodec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonCodec")
+            .field("max_frame_length", &self.framing.max_frame_length())
+            .finish()
+    }
+}
+
+impl<T> Decoder for JsonCodec<T>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Item = T;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, io::Error> {
+        match self.framing.decode(src)? {
+            Some(frame) => Ok(Some(serde_json::from_slice(&frame)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: Serialize> Encoder for JsonCodec<T> {
+    type Item = T;
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), io::Error> {
+        let buf = serde_json::to_vec(&item)?;
+        self.framing.encode(buf.into(), dst)
+    }
+}
@@ -19,7 +19,7 @@
 //!
 //! use tokio::{codec::{FramedWrite, LengthDelimitedCodec}, net::TcpStream};
 //!
-//! use tokio_serde_json::WriteJson;
+//! use tokio_serde_codecs::{Json, WriteJson};
 //!
 //! #[tokio::main]
 //! async fn main() {
@@ -32,7 +32,7 @@
 //!     let length_delimited = FramedWrite::new(socket, LengthDelimitedCodec::new());
 //!
 //!     // Serialize frames with JSON
-//!     let mut serialized = WriteJson::new(length_delimited);
+//!     let mut serialized = WriteJson::new(length_delimited, Json::new());
 //!
 //!     // Send the value
 //!     serialized.send(json!({
@@ -48,42 +48,313 @@
 //!
 //! For a full working server and client example, see the [examples] directory.
 //!
+//! Peers sending empty frames, e.g. as keepalives, can be dealt with by
+//! putting an [`EmptyFrames`] adaptor between the framing layer and the
+//! deserializing one. Frames that fail to deserialize can likewise be
+//! skipped instead of ending the stream by reading them through
+//! [`Recovering`] rather than `FramedRead`.
+//!
+//! With the `compress` feature enabled, frames of any of the serializers can
+//! be compressed with gzip or deflate by wrapping it in [`Compressed`].
+//! Likewise, the `base64` feature provides [`Base64`], which encodes frames
+//! as text for transports that cannot carry binary data. The `checksum`
+//! feature adds [`Checked`], which appends a CRC-32 or xxHash32 checksum to
+//! each frame and rejects corrupted frames with a [`ChecksumMismatch`].
+//!
+//! With the `codec` feature enabled, [`NdJson`] is also available. It is a
+//! `Decoder`/`Encoder` for newline-delimited JSON which does its own framing
+//! instead of relying on a separate framing layer, as is [`ConcatJson`] for
+//! objects and arrays following each other without any delimiter. The
+//! feature also adds
+//! [`read_json_from_io`] and [`write_json_from_io`], which set up length
+//! delimited framing and the JSON layer on top of an I/O object in one call,
+//! and [`JsonCodec`], a `Decoder`/`Encoder` producing the same length
+//! delimited frames for use with a plain `Framed`. Input without any framing
+//! at all, i.e. JSON values simply following each other, can be read with
+//! [`JsonStream`].
+//!
+//! Enabling the `simd` feature makes the JSON deserializers parse using
+//! [simd-json] instead of serde_json, without changing their API or errors.
+//! Frames simd-json rejects are parsed again by serde_json, which reports
+//! the error. The values read are the same, except that simd-json reads `-0`
+//! as the integer 0 rather than the float -0.0.
+//!
+//! The `arbitrary-precision` feature enables serde_json's feature of the same
+//! name, so that numbers decoded into `serde_json::Number` or
+//! `serde_json::Value` keep all of their digits instead of being rounded to
+//! `f64`. This matters e.g. for monetary amounts or big integers. simd-json
+//! cannot keep the digits, so with this feature frames are always parsed
+//! by serde_json, even if `simd` is enabled as well.
+//!
+//! The `preserve-order` feature enables serde_json's `preserve_order`
+//! feature, so that objects decoded into `serde_json::Value` keep their keys
+//! in the order they appeared in the frame, and are written back in that
+//! order.
+//!
+//! The `raw-value` feature enables serde_json's `raw_value` feature, so that
+//! messages or parts of them can be deserialized as `RawValue`s, which
+//! capture the bytes of a value without converting it. [`RawJson`] passes
+//! whole messages through this way.
+//!
+//! Servers handling many connections can share the buffers values are
+//! serialized into through a [`BufferPool`], from which every [`PooledJson`]
+//! takes a buffer per frame.
+//!
+//! A result set too large to buffer can be streamed as a single JSON array
+//! over a transport without framing using [`JsonArrayWriter`], which encodes
+//! the array one element at a time.
+//!
+//! The `validated` feature adds [`ValidatedJson`], which checks inbound
+//! values against a [JSON Schema] using [jsonschema] before deserializing
+//! them.
+//!
+//! Frames of any of the serializers can carry a schema version by wrapping
+//! it in [`Versioned`], which rejects frames of other versions.
+//!
+//! To multiplex several kinds of messages over one transport, [`Tagged`]
+//! prefixes the JSON contents of an enum with a single byte identifying the
+//! variant.
+//!
+//! With the `tracing` feature enabled, the serializers and deserializers of
+//! every format emit a [tracing] span at the debug level around each frame.
+//! The spans have the target `tokio_serde_codecs`, are named `serialize` and
+//! `deserialize`, and record the name of the codec, e.g. `json`, the length
+//! of the frame in bytes and, in case of failure, the error.
+//!
+//! Similarly, the `metrics` feature makes them record the counters
+//! `frames_encoded`, `bytes_encoded`, `frames_decoded` and `bytes_decoded`
+//! through the [metrics] facade, labelled with the name of the codec as
+//! `codec`. Only frames which have been processed successfully are counted.
+//!
+//! # Other formats
+//!
+//! JSON support is controlled by the `json` feature, which is enabled by
+//! default. Additional formats are available behind Cargo features of their
+//! own, so that only the formats in use get compiled:
+//!
+//! * `messagepack` - [`MessagePack`] using [rmp-serde].
+//! * `bincode` - [`Bincode`] using [bincode].
+//! * `cbor` - [`Cbor`] using [ciborium].
+//! * `yaml` - [`Yaml`] using [serde_yaml].
+//! * `toml` - [`Toml`] using [toml].
+//! * `postcard` - [`Postcard`] using [postcard].
+//! * `bson` - [`Bson`] using [bson].
+//! * `flexbuffers` - [`FlexBuffers`] using [flexbuffers].
+//! * `ron` - [`Ron`] using [ron].
+//! * `urlencoded` - [`UrlEncoded`] using [serde_urlencoded].
+//! * `avro` - [`Avro`] using [apache-avro].
+//!
+//! The `transcode` feature enables both JSON and MessagePack, and adds
+//! [`transcode_json_to_msgpack`] and [`transcode_msgpack_to_json`] to
+//! convert frames between the two formats without deserializing them into
+//! concrete types.
+//!
+//! To pick the format at runtime, e.g. according to a negotiated content
+//! type, every codec can also be used as a [`DynCodec`] trait object, which
+//! converts between frames and `serde_json::Value`s.
+//!
 //! [`Bytes`]: https://docs.rs/bytes/0.4/bytes/struct.Bytes.html
 //! [`length_delimited`]: https://docs.rs/tokio-io/0.1/tokio_io/codec/length_delimited/index.html
 //! [tokio-io]: https://github.com/tokio-rs/tokio-io
 //! [examples]: https://github.com/carllerche/tokio-serde-json/tree/master/examples
+//! [`EmptyFrames`]: struct.EmptyFrames.html
+//! [`Recovering`]: struct.Recovering.html
+//! [`Compressed`]: struct.Compressed.html
+//! [`Base64`]: struct.Base64.html
+//! [`Checked`]: struct.Checked.html
+//! [`ChecksumMismatch`]: struct.ChecksumMismatch.html
+//! [`NdJson`]: struct.NdJson.html
+//! [`ConcatJson`]: struct.ConcatJson.html
+//! [`read_json_from_io`]: fn.read_json_from_io.html
+//! [`write_json_from_io`]: fn.write_json_from_io.html
+//! [`JsonCodec`]: struct.JsonCodec.html
+//! [`JsonStream`]: struct.JsonStream.html
+//! [simd-json]: https://docs.rs/simd-json
+//! [`RawJson`]: type.RawJson.html
+//! [`BufferPool`]: struct.BufferPool.html
+//! [`PooledJson`]: struct.PooledJson.html
+//! [`JsonArrayWriter`]: struct.JsonArrayWriter.html
+//! [`ValidatedJson`]: struct.ValidatedJson.html
+//! [JSON Schema]: https://json-schema.org
+//! [jsonschema]: https://docs.rs/jsonschema
+//! [`Versioned`]: struct.Versioned.html
+//! [`Tagged`]: struct.Tagged.html
+//! [tracing]: https://docs.rs/tracing
+//! [metrics]: https://docs.rs/metrics
+//! [`MessagePack`]: struct.MessagePack.html
+//! [rmp-serde]: https://docs.rs/rmp-serde
+//! [`Cbor`]: struct.Cbor.html
+//! [ciborium]: https://docs.rs/ciborium
+//! [`Bincode`]: struct.Bincode.html
+//! [bincode]: https://docs.rs/bincode
+//! [`Yaml`]: struct.Yaml.html
+//! [serde_yaml]: https://docs.rs/serde_yaml
+//! [`Toml`]: struct.Toml.html
+//! [toml]: https://docs.rs/toml
+//! [`Postcard`]: struct.Postcard.html
+//! [postcard]: https://docs.rs/postcard
+//! [`Bson`]: struct.Bson.html
+//! [bson]: https://docs.rs/bson
+//! [`FlexBuffers`]: struct.FlexBuffers.html
+//! [flexbuffers]: https://docs.rs/flexbuffers
+//! [`Ron`]: struct.Ron.html
+//! [ron]: https://docs.rs/ron
+//! [`UrlEncoded`]: struct.UrlEncoded.html
+//! [serde_urlencoded]: https://docs.rs/serde_urlencoded
+//! [`Avro`]: struct.Avro.html
+//! [apache-avro]: https://docs.rs/apache-avro
+//! [`transcode_json_to_msgpack`]: fn.transcode_json_to_msgpack.html
+//! [`transcode_msgpack_to_json`]: fn.transcode_msgpack_to_json.html
+//! [`DynCodec`]: trait.DynCodec.html
 
-use bytes::{Buf, Bytes, BytesMut, IntoBuf};
-use futures::prelude::*;
-use pin_project::pin_project;
-use serde::{Deserialize, Serialize};
-use tokio_serde::{Deserializer, FramedRead, FramedWrite, Serializer};
+#[cfg(feature = "json")]
+mod array;
+#[cfg(feature = "avro")]
+mod avro;
+#[cfg(feature = "base64")]
+mod base64;
+#[cfg(feature = "bincode")]
+mod bincode;
+#[cfg(feature = "bson")]
+mod bson;
+#[cfg(feature = "json")]
+mod canonical;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "checksum")]
+mod checksum;
+#[cfg(feature = "compress")]
+mod compress;
+#[cfg(feature = "codec")]
+mod concat;
+#[cfg(feature = "json")]
+mod dynamic;
+mod empty;
+mod error;
+#[cfg(feature = "flexbuffers")]
+mod flexbuffers;
+#[cfg(any(
+    feature = "avro",
+    feature = "bincode",
+    feature = "bson",
+    feature = "cbor",
+    feature = "flexbuffers",
+    feature = "json",
+    feature = "messagepack",
+    feature = "postcard",
+    feature = "ron",
+    feature = "toml",
+    feature = "urlencoded",
+    feature = "yaml"
+))]
+mod format;
+mod instrument;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "codec")]
+mod length_delimited;
+#[cfg(feature = "json")]
+mod limits;
+#[cfg(feature = "messagepack")]
+mod messagepack;
+#[cfg(feature = "codec")]
+mod ndjson;
+#[cfg(feature = "json")]
+mod number;
+#[cfg(feature = "json")]
+mod pool;
+#[cfg(feature = "postcard")]
+mod postcard;
+mod recover;
+#[cfg(feature = "ron")]
+mod ron;
+#[cfg(feature = "json")]
+mod sort;
+#[cfg(feature = "codec")]
+mod stream;
+#[cfg(feature = "json")]
+mod tagged;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "transcode")]
+mod transcode;
+#[cfg(feature = "urlencoded")]
+mod urlencoded;
+#[cfg(feature = "validated")]
+mod validated;
+mod versioned;
+#[cfg(feature = "yaml")]
+mod yaml;
 
-use std::{
-    marker::PhantomData,
-    pin::Pin,
-    task::{Context, Poll},
+#[cfg(feature = "json")]
+pub use crate::array::JsonArrayWriter;
+#[cfg(feature = "avro")]
+pub use crate::avro::{Avro, ReadAvro, WriteAvro};
+#[cfg(feature = "base64")]
+pub use crate::base64::Base64;
+#[cfg(feature = "bincode")]
+pub use crate::bincode::{Bincode, ReadBincode, WriteBincode};
+#[cfg(feature = "bson")]
+pub use crate::bson::{Bson, ReadBson, WriteBson};
+#[cfg(feature = "json")]
+pub use crate::canonical::CanonicalJson;
+#[cfg(feature = "cbor")]
+pub use crate::cbor::{Cbor, ReadCbor, WriteCbor};
+#[cfg(feature = "checksum")]
+pub use crate::checksum::{Checked, ChecksumAlgorithm};
+#[cfg(feature = "compress")]
+pub use crate::compress::{Compressed, CompressionFormat};
+#[cfg(feature = "codec")]
+pub use crate::concat::ConcatJson;
+#[cfg(feature = "json")]
+pub use crate::dynamic::{BoxError, DynCodec};
+pub use crate::empty::{EmptyFramePolicy, EmptyFrames};
+#[cfg(feature = "checksum")]
+pub use crate::error::ChecksumMismatch;
+#[cfg(feature = "json")]
+pub use crate::error::JsonError;
+#[cfg(feature = "validated")]
+pub use crate::error::SchemaViolation;
+pub use crate::error::{into_io_error, CodecError, VersionMismatch};
+#[cfg(feature = "flexbuffers")]
+pub use crate::flexbuffers::{FlexBuffers, ReadFlexBuffers, WriteFlexBuffers};
+#[cfg(feature = "raw-value")]
+pub use crate::json::RawJson;
+#[cfg(feature = "json")]
+pub use crate::json::{
+    borrow_deserialize, decode, encode, BufferedJson, CompactJson, Formatted, FormattedJson, Json,
+    JsonDecode, JsonWith, PrettyJson, ReadJson, SymmetricalJson, WriteJson,
 };
-
-pub struct Json<T> {
-    ghost: PhantomData<T>,
-}
-
-impl<T> Deserializer<T> for Json<T>
-where
-    for<'a> T: Deserialize<'a>,
-{
-    type Error = serde_json::Error;
-
-    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
-        serde_json::from_reader(src.into_buf().reader())
-    }
-}
-
-impl<T: Serialize> Serializer<T> for Json<T> {
-    type Error = serde_json::Error;
-
-    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
-        serde_json::to_vec(item).map(Into::into)
-    }
-}
+#[cfg(feature = "codec")]
+pub use crate::length_delimited::{
+    read_json_from_io, read_json_from_io_with, write_json_from_io, write_json_from_io_with,
+    JsonCodec,
+};
+#[cfg(feature = "messagepack")]
+pub use crate::messagepack::{MessagePack, ReadMessagePack, WriteMessagePack};
+#[cfg(feature = "codec")]
+pub use crate::ndjson::NdJson;
+#[cfg(feature = "json")]
+pub use crate::number::NumberPolicy;
+#[cfg(feature = "json")]
+pub use crate::pool::{BufferPool, PooledJson};
+#[cfg(feature = "postcard")]
+pub use crate::postcard::{Postcard, ReadPostcard, WritePostcard};
+pub use crate::recover::{ErrorPolicy, Recovering};
+#[cfg(feature = "ron")]
+pub use crate::ron::{ReadRon, Ron, WriteRon};
+#[cfg(feature = "codec")]
+pub use crate::stream::JsonStream;
+#[cfg(feature = "json")]
+pub use crate::tagged::Tagged;
+#[cfg(feature = "toml")]
+pub use crate::toml::{ReadToml, Toml, WriteToml};
+#[cfg(feature = "transcode")]
+pub use crate::transcode::{transcode_json_to_msgpack, transcode_msgpack_to_json};
+#[cfg(feature = "urlencoded")]
+pub use crate::urlencoded::{ReadUrlEncoded, UrlEncoded, WriteUrlEncoded};
+#[cfg(feature = "validated")]
+pub use crate::validated::ValidatedJson;
+pub use crate::versioned::Versioned;
+#[cfg(feature = "yaml")]
+pub use crate::yaml::{ReadYaml, WriteYaml, Yaml};
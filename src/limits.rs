@@ -0,0 +1,428 @@
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::number::RAW_VALUE;
+
+/// Structural limits checked while deserializing JSON.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Limits {
+    pub(crate) max_elements: Option<usize>,
+    pub(crate) max_string_len: Option<usize>,
+}
+
+impl Limits {
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.max_elements.is_none() && self.max_string_len.is_none()
+    }
+
+    fn check_string<E: de::Error>(&self, len: usize) -> Result<(), E> {
+        match self.max_string_len {
+            Some(max) if len > max => Err(E::custom(format_args!(
+                "string exceeds maximum length of {} bytes",
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Deserializer proxy enforcing `Limits` on the values handed to visitors.
+///
+/// The number of elements of arrays and members of objects is counted as
+/// they are parsed, so that parsing stops as soon as a limit is exceeded
+/// instead of after the whole value has been built.
+pub(crate) struct Limited<D> {
+    inner: D,
+    limits: Limits,
+}
+
+impl<D> Limited<D> {
+    pub(crate) fn new(inner: D, limits: Limits) -> Self {
+        Limited { inner, limits }
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident,)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+                self.inner.$method(Visitor::new(visitor, self.limits))
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Limited<D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = Visitor::new(visitor, self.limits);
+        self.inner.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        // The text of a `RawValue` is handed to the visitor as a string,
+        // which must not count towards `max_string_len`
+        if name == RAW_VALUE {
+            return self.inner.deserialize_newtype_struct(name, visitor);
+        }
+        let visitor = Visitor::new(visitor, self.limits);
+        self.inner.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = Visitor::new(visitor, self.limits);
+        self.inner.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = Visitor::new(visitor, self.limits);
+        self.inner.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = Visitor::new(visitor, self.limits);
+        self.inner.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = Visitor::new(visitor, self.limits);
+        self.inner.deserialize_enum(name, variants, visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct Seed<S> {
+    inner: S,
+    limits: Limits,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Seed<S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.inner
+            .deserialize(Limited::new(deserializer, self.limits))
+    }
+}
+
+/// Seed failing without parsing anything, handed out in place of the seed
+/// of an element beyond the limit.
+struct Exceeded<T> {
+    max: usize,
+    ghost: PhantomData<T>,
+}
+
+impl<T> Exceeded<T> {
+    fn new(max: usize) -> Self {
+        Exceeded {
+            max,
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for Exceeded<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, _: D) -> Result<T, D::Error> {
+        Err(de::Error::custom(format_args!(
+            "array or object exceeds maximum of {} elements",
+            self.max
+        )))
+    }
+}
+
+struct Visitor<V> {
+    inner: V,
+    limits: Limits,
+}
+
+impl<V> Visitor<V> {
+    fn new(inner: V, limits: Limits) -> Self {
+        Visitor { inner, limits }
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V: de::Visitor<'de>> de::Visitor<'de> for Visitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<V::Value, E> {
+        self.limits.check_string(v.len())?;
+        self.inner.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<V::Value, E> {
+        self.limits.check_string(v.len())?;
+        self.inner.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<V::Value, E> {
+        self.limits.check_string(v.len())?;
+        self.inner.visit_string(v)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<V::Value, E> {
+        self.limits.check_string(v.len())?;
+        self.inner.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<V::Value, E> {
+        self.limits.check_string(v.len())?;
+        self.inner.visit_borrowed_bytes(v)
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<V::Value, E> {
+        self.limits.check_string(v.len())?;
+        self.inner.visit_byte_buf(v)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.inner
+            .visit_some(Limited::new(deserializer, self.limits))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_unit()
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.inner
+            .visit_newtype_struct(Limited::new(deserializer, self.limits))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_seq(Access::new(seq, self.limits))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_map(Access::new(map, self.limits))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_enum(Access::new(data, self.limits))
+    }
+}
+
+/// Proxy for the accessors handed to visitors, counting the elements of
+/// arrays and members of objects.
+struct Access<A> {
+    inner: A,
+    limits: Limits,
+    count: usize,
+}
+
+impl<A> Access<A> {
+    fn new(inner: A, limits: Limits) -> Self {
+        Access {
+            inner,
+            limits,
+            count: 0,
+        }
+    }
+
+    /// Counts another element, returning the limit if it is exceeded.
+    fn exceeded(&mut self) -> Option<usize> {
+        self.count += 1;
+        self.limits.max_elements.filter(|&max| self.count > max)
+    }
+
+    fn seed<S>(&self, inner: S) -> Seed<S> {
+        Seed {
+            inner,
+            limits: self.limits,
+        }
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Access<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        match self.exceeded() {
+            Some(max) => self.inner.next_element_seed(Exceeded::new(max)),
+            None => {
+                let seed = self.seed(seed);
+                self.inner.next_element_seed(seed)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Access<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        match self.exceeded() {
+            Some(max) => self.inner.next_key_seed(Exceeded::new(max)),
+            None => {
+                let seed = self.seed(seed);
+                self.inner.next_key_seed(seed)
+            }
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: EnumAccess<'de>> EnumAccess<'de> for Access<A> {
+    type Error = A::Error;
+    type Variant = Access<A::Variant>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), A::Error> {
+        let limits = self.limits;
+        let seed = self.seed(seed);
+        let (value, variant) = self.inner.variant_seed(seed)?;
+        Ok((value, Access::new(variant, limits)))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for Access<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        let seed = self.seed(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.inner
+            .tuple_variant(len, Visitor::new(visitor, self.limits))
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.inner
+            .struct_variant(fields, Visitor::new(visitor, self.limits))
+    }
+}
@@ -0,0 +1,130 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of MessagePack encoded buffers to a stream of values.
+pub type ReadMessagePack<T, U> = FramedRead<T, U, MessagePack<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as MessagePack.
+pub type WriteMessagePack<T, U> = FramedWrite<T, U, MessagePack<U>>;
+
+/// MessagePack serializer and deserializer backed by [rmp-serde].
+///
+/// Values are written using the compact representation by default: structs
+/// are encoded as arrays of their fields, without the field names. [`named`]
+/// creates a `MessagePack` writing structs as maps keyed by the field names
+/// instead, which is larger but lets peers tolerate reordered or added
+/// fields. Both representations are accepted when reading.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::MessagePack;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Point {
+///     latitude: f32,
+///     longitude: f32,
+/// }
+///
+/// let point = Point { latitude: 52.5, longitude: 13.4 };
+/// let contains = |frame: &[u8], name: &str| {
+///     frame.windows(name.len()).any(|window| window == name.as_bytes())
+/// };
+///
+/// let mut compact = MessagePack::<Point>::new();
+/// let frame = Pin::new(&mut compact).serialize(&point).unwrap();
+/// assert!(!contains(&frame, "latitude"));
+///
+/// let mut named = MessagePack::<Point>::named();
+/// let frame = Pin::new(&mut named).serialize(&point).unwrap();
+/// assert!(contains(&frame, "latitude") && contains(&frame, "longitude"));
+///
+/// // Either deserializer reads both representations
+/// assert_eq!(Pin::new(&mut compact).deserialize(&frame.into()).unwrap(), point);
+/// ```
+///
+/// [rmp-serde]: https://docs.rs/rmp-serde
+/// [`named`]: #method.named
+pub struct MessagePack<T> {
+    named: bool,
+    ghost: PhantomData<T>,
+}
+
+impl<T> MessagePack<T> {
+    /// Creates a new MessagePack serializer and deserializer writing structs
+    /// as arrays.
+    pub fn new() -> Self {
+        MessagePack {
+            named: false,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Creates a new MessagePack serializer and deserializer writing structs
+    /// as maps with the names of their fields.
+    pub fn named() -> Self {
+        MessagePack {
+            named: true,
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for MessagePack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for MessagePack<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MessagePack<T> {}
+
+impl<T> fmt::Debug for MessagePack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessagePack")
+            .field("named", &self.named)
+            .finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for MessagePack<T> {
+    const NAME: &'static str = "messagepack";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        let buf = if self.named {
+            rmp_serde::to_vec_named(item)?
+        } else {
+            rmp_serde::to_vec(item)?
+        };
+        Ok(buf.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(rmp_serde::from_slice(src)?)
+    }
+}
+
+impl_symmetrical_codec!(MessagePack);
@@ -0,0 +1,221 @@
+use bytes::BytesMut;
+use serde::{Deserialize, Serialize};
+use tokio_codec::{Decoder, Encoder};
+
+use std::{fmt, io, marker::PhantomData};
+
+use crate::{CodecError, JsonError};
+
+/// Codec for newline-delimited JSON.
+///
+/// Unlike [`Json`], this handles framing by itself: every value is written
+/// on its own line followed by `\n`, and input is split on raw `\n` bytes,
+/// with each line parsed as exactly one value. A line that has not been
+/// terminated yet stays buffered until the rest of it arrives, except at the
+/// end of the stream where a final line without a trailing newline is still
+/// decoded. Empty lines are skipped. As a line is buffered until it is
+/// complete, setting a [maximum line length] is recommended when reading from
+/// untrusted peers.
+///
+/// Splitting on raw newlines is safe because JSON strings cannot contain
+/// them: a newline inside a string is always escaped as `\n`. Values must
+/// therefore be written compactly; pretty printed JSON is not valid here.
+///
+/// Errors are reported as a [`CodecError`], like with the other codecs of
+/// this crate: lines which do not parse fail with [`CodecError::Json`], while
+/// errors of the transport and overlong lines fail with [`CodecError::Io`].
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use serde_json::{json, Value};
+/// use tokio_codec::Decoder;
+/// use tokio_serde_codecs::NdJson;
+///
+/// let mut codec = NdJson::<Value>::new();
+/// let mut buf = BytesMut::new();
+/// let mut values = Vec::new();
+///
+/// // Bytes arriving one at a time are kept until the line is complete
+/// for &b in b"{\"a\":[1,\"\\n\"]}\n" {
+///     buf.extend_from_slice(&[b]);
+///     values.extend(codec.decode(&mut buf).unwrap());
+/// }
+///
+/// assert_eq!(values, vec![json!({"a": [1, "\n"]})]);
+/// assert!(buf.is_empty());
+/// ```
+///
+/// A line split across two reads, followed by a final line which the peer
+/// did not terminate before closing the stream:
+///
+/// ```
+/// use bytes::BytesMut;
+/// use serde_json::{json, Value};
+/// use tokio_codec::Decoder;
+/// use tokio_serde_codecs::{CodecError, NdJson};
+///
+/// let mut codec = NdJson::<Value>::new();
+///
+/// let mut buf = BytesMut::from(&b"{\"id\":1,"[..]);
+/// assert_eq!(codec.decode(&mut buf).unwrap(), None);
+///
+/// buf.extend_from_slice(b"\"ok\":true}\n{\"id\":2}");
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({"id": 1, "ok": true})));
+/// assert_eq!(codec.decode(&mut buf).unwrap(), None);
+///
+/// // At the end of the stream the final line is decoded nonetheless
+/// assert_eq!(codec.decode_eof(&mut buf).unwrap(), Some(json!({"id": 2})));
+/// assert_eq!(codec.decode_eof(&mut buf).unwrap(), None);
+///
+/// // A truncated final line is an error rather than being dropped
+/// let mut buf = BytesMut::from(&b"{\"id\":3"[..]);
+/// match codec.decode_eof(&mut buf) {
+///     Err(CodecError::Json(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [`Json`]: struct.Json.html
+/// [`CodecError`]: enum.CodecError.html
+/// [`CodecError::Json`]: enum.CodecError.html#variant.Json
+/// [`CodecError::Io`]: enum.CodecError.html#variant.Io
+/// [maximum line length]: #method.max_len
+pub struct NdJson<T> {
+    // Index of the next byte to check for a newline, so that a partial line
+    // is not searched again on every read.
+    next_index: usize,
+    max_len: Option<usize>,
+    ghost: PhantomData<T>,
+}
+
+impl<T> NdJson<T> {
+    /// Creates a new newline-delimited JSON codec.
+    pub fn new() -> Self {
+        NdJson {
+            next_index: 0,
+            max_len: None,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length in bytes of a line, excluding the newline.
+    ///
+    /// Decoding fails as soon as more bytes than this have been buffered for
+    /// a single line, instead of waiting for the line to end. There is no
+    /// limit by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    ///
+    /// use bytes::BytesMut;
+    /// use serde_json::Value;
+    /// use tokio_codec::Decoder;
+    /// use tokio_serde_codecs::{CodecError, NdJson};
+    ///
+    /// let mut codec = NdJson::<Value>::new().max_len(8);
+    ///
+    /// // The line is rejected before its end arrives, without parsing it
+    /// let mut buf = BytesMut::from(&b"[1,2,3,garbage"[..]);
+    /// match codec.decode(&mut buf) {
+    ///     Err(CodecError::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::InvalidData),
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), CodecError> {
+        match self.max_len {
+            Some(max_len) if len > max_len => Err(CodecError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line exceeds maximum length of {} bytes", max_len),
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T> Default for NdJson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for NdJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NdJson")
+            .field("max_len", &self.max_len)
+            .finish()
+    }
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(|b| b.is_ascii_whitespace())
+}
+
+fn parse<T>(line: &[u8]) -> Result<T, CodecError>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    serde_json::from_slice(line).map_err(|err| JsonError::new(err, line).into())
+}
+
+impl<T> Decoder for NdJson<T>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Item = T;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, CodecError> {
+        while let Some(offset) = src[self.next_index..].iter().position(|b| *b == b'\n') {
+            self.check_len(self.next_index + offset)?;
+
+            let line = src.split_to(self.next_index + offset + 1);
+            self.next_index = 0;
+
+            if !is_blank(&line) {
+                return parse(&line).map(Some);
+            }
+        }
+
+        self.check_len(src.len())?;
+
+        self.next_index = src.len();
+        Ok(None)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<T>, CodecError> {
+        if let Some(item) = self.decode(src)? {
+            return Ok(Some(item));
+        }
+
+        let line = src.take();
+        self.next_index = 0;
+
+        if is_blank(&line) {
+            Ok(None)
+        } else {
+            parse(&line).map(Some)
+        }
+    }
+}
+
+impl<T: Serialize> Encoder for NdJson<T> {
+    type Item = T;
+    type Error = CodecError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), CodecError> {
+        let buf = serde_json::to_vec(&item)?;
+        dst.reserve(buf.len() + 1);
+        dst.extend_from_slice(&buf);
+        dst.extend_from_slice(b"\n");
+        Ok(())
+    }
+}
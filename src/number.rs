@@ -0,0 +1,1047 @@
+use serde::{
+    de::{
+        self, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+        VariantAccess,
+    },
+    ser::{self, Serialize, Serializer},
+    Deserialize,
+};
+use serde_json::ser::Formatter;
+
+use std::{borrow::Cow, cell::Cell, fmt, io};
+
+use crate::{
+    json::deserialize_each,
+    limits::{Limited, Limits},
+};
+
+/// How the JSON codecs handle floats which are NaN or infinite.
+///
+/// Standard JSON ([RFC 8259]) has no representation for these values. Some
+/// producers nevertheless emit the JavaScript literals `NaN`, `Infinity` and
+/// `-Infinity`.
+///
+/// [RFC 8259]: https://tools.ietf.org/html/rfc8259
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberPolicy {
+    /// Fail to serialize non-finite floats, and reject the non-standard
+    /// literals when deserializing.
+    #[default]
+    Reject,
+    /// Serialize non-finite floats as `null`, and reject the non-standard
+    /// literals when deserializing.
+    ///
+    /// This is what serde_json does on its own.
+    Null,
+    /// Serialize non-finite floats as `NaN`, `Infinity` and `-Infinity`, and
+    /// accept these literals when deserializing.
+    ///
+    /// The output is not valid JSON, so only use this with peers which
+    /// expect it.
+    ///
+    /// Strings in the frame are never taken for the literals, the literals
+    /// don't count towards [`max_string_len`], and errors point at the
+    /// position in the frame as it was received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use bytes::BytesMut;
+    /// use serde::Deserialize;
+    /// use tokio_serde::Deserializer;
+    /// use tokio_serde_codecs::{Json, NumberPolicy};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// #[serde(untagged)]
+    /// enum Value {
+    ///     Float(f64),
+    ///     Text(String),
+    /// }
+    ///
+    /// let mut json = Json::<Vec<Value>>::new()
+    ///     .number_policy(NumberPolicy::Literal)
+    ///     .max_string_len(32);
+    ///
+    /// let frame = br#"[NaN,"N","NaN","\u0000tokio-serde-codecs:NaN",Infinity]"#;
+    /// let values = Pin::new(&mut json).deserialize(&BytesMut::from(&frame[..])).unwrap();
+    /// assert!(matches!(values[0], Value::Float(v) if v.is_nan()));
+    /// assert!(matches!(&values[1], Value::Text(s) if s == "N"));
+    /// assert!(matches!(&values[2], Value::Text(s) if s == "NaN"));
+    /// assert!(matches!(&values[3], Value::Text(s) if s == "\0tokio-serde-codecs:NaN"));
+    /// assert!(matches!(values[4], Value::Float(v) if v == f64::INFINITY));
+    ///
+    /// let mut json = Json::<Vec<f64>>::new().number_policy(NumberPolicy::Literal);
+    /// let frame = BytesMut::from(&b"[-Infinity,NaN,x]"[..]);
+    /// let err = Pin::new(&mut json).deserialize(&frame).unwrap_err();
+    /// assert!(err.to_string().contains("line 1 column 16"), "{}", err);
+    /// ```
+    ///
+    /// [`max_string_len`]: struct.Json.html#method.max_string_len
+    Literal,
+}
+
+const NAN: &str = "NaN";
+const INFINITY: &str = "Infinity";
+const NEG_INFINITY: &str = "-Infinity";
+
+/// Serializes `value` as compact JSON, applying `policy` to non-finite
+/// floats, and escaping all non-ASCII characters if `ascii_only` is set.
+///
+/// serde_json always writes non-finite floats as `null`, which is all
+/// [`NumberPolicy::Null`] asks for. For the other policies the value is
+/// serialized through a proxy intercepting floats and forwarding everything
+/// else to serde_json. With [`NumberPolicy::Reject`] this is only done to
+/// look for non-finite floats once serde_json has written a `null`, keeping
+/// the proxy off the path of most values.
+///
+/// [`NumberPolicy::Null`]: enum.NumberPolicy.html#variant.Null
+/// [`NumberPolicy::Reject`]: enum.NumberPolicy.html#variant.Reject
+pub(crate) fn to_writer<W, T>(
+    writer: W,
+    value: &T,
+    policy: NumberPolicy,
+    ascii_only: bool,
+) -> Result<(), serde_json::Error>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+{
+    let state = State {
+        policy,
+        literal: Cell::new(None),
+        wrote_null: Cell::new(false),
+    };
+    let formatter = LiteralFormatter {
+        state: &state,
+        ascii_only,
+    };
+    let mut serializer = serde_json::Serializer::with_formatter(writer, formatter);
+
+    if policy == NumberPolicy::Literal {
+        return value.serialize(Ser {
+            inner: &mut serializer,
+            state: &state,
+        });
+    }
+
+    let result = value.serialize(&mut serializer);
+    if policy == NumberPolicy::Reject && state.wrote_null.get() {
+        // The output is the same as through the proxy unless it fails
+        value.serialize(Ser {
+            inner: &mut serde_json::Serializer::new(io::sink()),
+            state: &state,
+        })?;
+    }
+    result
+}
+
+/// Parses the JSON value held by a frame, accepting the literals for
+/// non-finite floats.
+///
+/// serde_json rejects the literals, so they are replaced by strings before
+/// parsing, and turned into floats again by a proxy deserializer forwarding
+/// everything else to serde_json.
+pub(crate) fn from_slice_literal<T>(
+    src: &[u8],
+    allow_trailing: bool,
+    limits: Limits,
+) -> Result<T, serde_json::Error>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    let (src, values) = Literals::replace(src);
+    let literals = Literals::new(&src, values);
+    let mut deserializer = serde_json::Deserializer::from_slice(&src);
+    let value = T::deserialize(Limited::new(De(&mut deserializer, &literals), limits))?;
+
+    if !allow_trailing {
+        deserializer.end()?;
+    }
+
+    Ok(value)
+}
+
+/// Parses every JSON value of a frame holding several concatenated ones,
+/// accepting the literals for non-finite floats like [`from_slice_literal`].
+///
+/// [`from_slice_literal`]: fn.from_slice_literal.html
+pub(crate) fn from_slice_literal_many<T>(
+    src: &[u8],
+    limits: Limits,
+) -> Vec<Result<T, serde_json::Error>>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    let (src, values) = Literals::replace(src);
+    let literals = Literals::new(&src, values);
+    let mut deserializer = serde_json::Deserializer::from_slice(&src);
+    deserialize_each(&mut deserializer, |deserializer| {
+        T::deserialize(Limited::new(De(deserializer, &literals), limits))
+    })
+}
+
+struct State {
+    policy: NumberPolicy,
+    // Literal to write instead of the next `null`.
+    literal: Cell<Option<&'static str>>,
+    // Whether a `null` has been written.
+    wrote_null: Cell<bool>,
+}
+
+impl State {
+    fn serialize_non_finite<S: Serializer>(&self, inner: S, v: f64) -> Result<S::Ok, S::Error> {
+        match self.policy {
+            NumberPolicy::Reject => Err(ser::Error::custom(format_args!(
+                "cannot serialize non-finite float {}",
+                v
+            ))),
+            NumberPolicy::Null => inner.serialize_unit(),
+            NumberPolicy::Literal => {
+                self.literal.set(Some(if v.is_nan() {
+                    NAN
+                } else if v > 0.0 {
+                    INFINITY
+                } else {
+                    NEG_INFINITY
+                }));
+                inner.serialize_unit()
+            }
+        }
+    }
+}
+
+/// Compact formatter writing a pending literal in place of `null`.
+struct LiteralFormatter<'a> {
+    state: &'a State,
+    // Whether to write non-ASCII characters as `\u` escapes.
+    ascii_only: bool,
+}
+
+impl Formatter for LiteralFormatter<'_> {
+    fn write_null<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        let literal = self.state.literal.take().unwrap_or("null");
+        self.state.wrote_null.set(true);
+        writer.write_all(literal.as_bytes())
+    }
+
+    fn write_string_fragment<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        fragment: &str,
+    ) -> io::Result<()> {
+        if !self.ascii_only {
+            return writer.write_all(fragment.as_bytes());
+        }
+
+        // serde_json has already escaped the ASCII characters that need it,
+        // so only the others are left
+        let mut start = 0;
+        for (i, c) in fragment.char_indices().filter(|(_, c)| !c.is_ascii()) {
+            writer.write_all(&fragment.as_bytes()[start..i])?;
+            // Characters outside the Basic Multilingual Plane take a
+            // surrogate pair
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                write!(writer, "\\u{:04x}", unit)?;
+            }
+            start = i + c.len_utf8();
+        }
+        writer.write_all(&fragment.as_bytes()[start..])
+    }
+}
+
+/// Value serialized through a [`Ser`] proxy.
+struct Wrap<'a, T: ?Sized> {
+    value: &'a T,
+    state: &'a State,
+}
+
+impl<T: ?Sized + Serialize> Serialize for Wrap<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Ser {
+            inner: serializer,
+            state: self.state,
+        })
+    }
+}
+
+struct Ser<'a, S> {
+    inner: S,
+    state: &'a State,
+}
+
+macro_rules! forward_serialize {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'a, S: Serializer> Serializer for Ser<'a, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<'a, S::SerializeSeq>;
+    type SerializeTuple = Compound<'a, S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<'a, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<'a, S::SerializeTupleVariant>;
+    type SerializeMap = Compound<'a, S::SerializeMap>;
+    type SerializeStruct = Compound<'a, S::SerializeStruct>;
+    type SerializeStructVariant = Compound<'a, S::SerializeStructVariant>;
+
+    forward_serialize! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        if v.is_finite() {
+            self.inner.serialize_f32(v)
+        } else {
+            self.state.serialize_non_finite(self.inner, v.into())
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        if v.is_finite() {
+            self.inner.serialize_f64(v)
+        } else {
+            self.state.serialize_non_finite(self.inner, v)
+        }
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<S::Ok, S::Error> {
+        let state = self.state;
+        self.inner.serialize_some(&Wrap { value, state })
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let state = self.state;
+        self.inner
+            .serialize_newtype_struct(name, &Wrap { value, state })
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let state = self.state;
+        self.inner
+            .serialize_newtype_variant(name, index, variant, &Wrap { value, state })
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let state = self.state;
+        let inner = self.inner.serialize_seq(len)?;
+        Ok(Compound { inner, state })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let state = self.state;
+        let inner = self.inner.serialize_tuple(len)?;
+        Ok(Compound { inner, state })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let state = self.state;
+        let inner = self.inner.serialize_tuple_struct(name, len)?;
+        Ok(Compound { inner, state })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let state = self.state;
+        let inner = self
+            .inner
+            .serialize_tuple_variant(name, index, variant, len)?;
+        Ok(Compound { inner, state })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let state = self.state;
+        let inner = self.inner.serialize_map(len)?;
+        Ok(Compound { inner, state })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let state = self.state;
+        let inner = self.inner.serialize_struct(name, len)?;
+        Ok(Compound { inner, state })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let state = self.state;
+        let inner = self
+            .inner
+            .serialize_struct_variant(name, index, variant, len)?;
+        Ok(Compound { inner, state })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Compound value serialized through a [`Ser`] proxy.
+struct Compound<'a, C> {
+    inner: C,
+    state: &'a State,
+}
+
+impl<C: ser::SerializeSeq> ser::SerializeSeq for Compound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_element(&Wrap { value, state })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTuple> ser::SerializeTuple for Compound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_element(&Wrap { value, state })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleStruct> ser::SerializeTupleStruct for Compound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_field(&Wrap { value, state })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleVariant> ser::SerializeTupleVariant for Compound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_field(&Wrap { value, state })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeMap> ser::SerializeMap for Compound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_key(&Wrap { value: key, state })
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_value(&Wrap { value, state })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStruct> ser::SerializeStruct for Compound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_field(key, &Wrap { value, state })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStructVariant> ser::SerializeStructVariant for Compound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        let state = self.state;
+        self.inner.serialize_field(key, &Wrap { value, state })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+/// Name under which serde_json deserializes a `RawValue`, whose text it
+/// then hands to the visitor as a string.
+pub(crate) const RAW_VALUE: &str = "$serde_json::private::RawValue";
+
+/// The literals for non-finite floats replaced in a frame.
+///
+/// Each literal is replaced by a string of the same length, e.g. `NaN` by
+/// `"N"`, so that the positions in errors still match the frame. The strings
+/// are told apart from ones in the frame by where they start rather than by
+/// their contents: serde_json borrows the contents of strings without
+/// escapes from the buffer it parses, and only the replaced strings start at
+/// the recorded positions.
+struct Literals {
+    // Address of the buffer with the replaced literals.
+    base: usize,
+    values: Replaced,
+}
+
+/// Positions of the contents of the replaced strings, in ascending order,
+/// and the literals they stand for.
+type Replaced = Vec<(usize, &'static str, f64)>;
+
+impl Literals {
+    /// Replaces the literals outside of strings, returning the resulting
+    /// buffer and the positions of the replaced strings in it.
+    fn replace(src: &[u8]) -> (Cow<'_, [u8]>, Replaced) {
+        let mut out: Option<Vec<u8>> = None;
+        let mut values = Vec::new();
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut i = 0;
+
+        while i < src.len() {
+            let b = src[i];
+
+            if in_string {
+                match b {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+            } else if b == b'"' {
+                in_string = true;
+            } else if let Some(&(literal, value)) = LITERALS
+                .iter()
+                .find(|(literal, _)| src[i..].starts_with(literal.as_bytes()))
+            {
+                let len = literal.len();
+                let token = &mut out.get_or_insert_with(|| src.to_vec())[i..i + len];
+                token.copy_within(..len - 2, 1);
+                token[0] = b'"';
+                token[len - 1] = b'"';
+
+                values.push((i + 1, literal, value));
+                i += len;
+                continue;
+            }
+
+            i += 1;
+        }
+
+        match out {
+            Some(out) => (Cow::Owned(out), values),
+            None => (Cow::Borrowed(src), values),
+        }
+    }
+
+    fn new(buf: &[u8], values: Replaced) -> Self {
+        Literals {
+            base: buf.as_ptr() as usize,
+            values,
+        }
+    }
+
+    /// Returns the float a string borrowed from the buffer stands for.
+    fn value(&self, s: &str) -> Option<f64> {
+        let pos = (s.as_ptr() as usize).wrapping_sub(self.base);
+        self.values
+            .binary_search_by_key(&pos, |&(pos, _, _)| pos)
+            .ok()
+            .map(|i| self.values[i].2)
+    }
+
+    /// Puts the literals back into the text of a `RawValue` borrowed from
+    /// the buffer.
+    fn restore(&self, raw: &str) -> String {
+        let start = (raw.as_ptr() as usize).wrapping_sub(self.base);
+        // The positions are those of the contents, after the quotes
+        let first = match self
+            .values
+            .binary_search_by_key(&(start + 1), |&(pos, _, _)| pos)
+        {
+            Ok(i) | Err(i) => i,
+        };
+
+        let mut out = raw.to_owned().into_bytes();
+        for &(pos, literal, _) in &self.values[first..] {
+            let offset = pos - 1 - start;
+            if offset >= out.len() {
+                break;
+            }
+            out[offset..offset + literal.len()].copy_from_slice(literal.as_bytes());
+        }
+
+        // Only ASCII has been replaced by ASCII
+        String::from_utf8(out).expect("RawValue text is UTF-8")
+    }
+}
+
+/// The literals, with the longest first in case one is a prefix of another.
+const LITERALS: [(&str, f64); 3] = [
+    (NEG_INFINITY, f64::NEG_INFINITY),
+    (INFINITY, f64::INFINITY),
+    (NAN, f64::NAN),
+];
+
+/// Deserializer proxy turning the replaced strings back into floats.
+struct De<'a, D>(D, &'a Literals);
+
+macro_rules! forward_deserialize {
+    ($($method:ident,)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+                self.0.$method(Visitor(visitor, self.1))
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for De<'_, D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+
+    // serde_json only hands strings to visitors from `deserialize_any`.
+
+    fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.0.deserialize_any(Float(visitor, self.1))
+    }
+
+    fn deserialize_f64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.0.deserialize_any(Float(visitor, self.1))
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0
+            .deserialize_unit_struct(name, Visitor(visitor, self.1))
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        if name == RAW_VALUE {
+            return self
+                .0
+                .deserialize_newtype_struct(name, Raw(visitor, self.1));
+        }
+        self.0
+            .deserialize_newtype_struct(name, Visitor(visitor, self.1))
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0.deserialize_tuple(len, Visitor(visitor, self.1))
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0
+            .deserialize_tuple_struct(name, len, Visitor(visitor, self.1))
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0
+            .deserialize_struct(name, fields, Visitor(visitor, self.1))
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0
+            .deserialize_enum(name, variants, Visitor(visitor, self.1))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+struct Seed<'a, S>(S, &'a Literals);
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Seed<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.0.deserialize(De(deserializer, self.1))
+    }
+}
+
+struct Visitor<'a, V>(V, &'a Literals);
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V: de::Visitor<'de>> de::Visitor<'de> for Visitor<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<V::Value, E> {
+        self.0.visit_str(v)
+    }
+
+    // The replaced strings have no escapes, so serde_json always lends them
+    // from the buffer.
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<V::Value, E> {
+        match self.1.value(v) {
+            Some(v) => self.0.visit_f64(v),
+            None => self.0.visit_borrowed_str(v),
+        }
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<V::Value, E> {
+        self.0.visit_string(v)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.0.visit_some(De(deserializer, self.1))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.0.visit_newtype_struct(De(deserializer, self.1))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.0.visit_seq(Access(seq, self.1))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.0.visit_map(Access(map, self.1))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.0.visit_enum(Access(data, self.1))
+    }
+}
+
+/// Visitor proxy for floats, also accepting the replaced strings.
+struct Float<'a, V>(V, &'a Literals);
+
+impl<'de, V: de::Visitor<'de>> de::Visitor<'de> for Float<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(f)
+    }
+
+    forward_visit! {
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f64(f64),
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<V::Value, E> {
+        self.0.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<V::Value, E> {
+        match self.1.value(v) {
+            Some(v) => self.0.visit_f64(v),
+            None => self.0.visit_borrowed_str(v),
+        }
+    }
+
+    // With serde_json's `arbitrary_precision` feature, which may also be
+    // enabled by another crate, `deserialize_any` hands out numbers as maps
+    // which only a `serde_json::Number` understands.
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        let number = serde_json::Number::deserialize(de::value::MapAccessDeserializer::new(map))?;
+
+        match number.as_f64() {
+            Some(v) => self.0.visit_f64(v),
+            None => Err(de::Error::custom("number out of range")),
+        }
+    }
+}
+
+/// Visitor proxy for `RawValue`s, restoring the literals in their text.
+///
+/// serde_json lends the text from the buffer, which tells where the replaced
+/// strings in it are. The types deserializing through `from_slice_literal`
+/// own their data, so the restored text doesn't need to be borrowed.
+struct Raw<'a, V>(V, &'a Literals);
+
+impl<'de, V: de::Visitor<'de>> de::Visitor<'de> for Raw<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(f)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.0.visit_map(Raw(map, self.1))
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Raw<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        self.0.next_key_seed(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        let raw: &'de str = self.0.next_value()?;
+        seed.deserialize(self.1.restore(raw).into_deserializer())
+    }
+}
+
+/// Proxy for the accessors handed to visitors.
+struct Access<'a, A>(A, &'a Literals);
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Access<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        self.0.next_element_seed(Seed(seed, self.1))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Access<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        self.0.next_key_seed(Seed(seed, self.1))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        self.0.next_value_seed(Seed(seed, self.1))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, 'de, A: EnumAccess<'de>> EnumAccess<'de> for Access<'a, A> {
+    type Error = A::Error;
+    type Variant = Access<'a, A::Variant>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), A::Error> {
+        let (value, variant) = self.0.variant_seed(Seed(seed, self.1))?;
+        Ok((value, Access(variant, self.1)))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for Access<'_, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        self.0.newtype_variant_seed(Seed(seed, self.1))
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.0.tuple_variant(len, Visitor(visitor, self.1))
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.0.struct_variant(fields, Visitor(visitor, self.1))
+    }
+}
@@ -0,0 +1,188 @@
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_serde::{Deserializer, Serializer};
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    instrument,
+    json::{from_slice, BytesWriter},
+    CodecError,
+};
+
+/// Pool of buffers shared by the [`PooledJson`] serializers of many
+/// connections.
+///
+/// A serializer takes a buffer out of the pool for every value, splits the
+/// frame off it and puts the rest back, so the capacity left over after one
+/// frame is used for the next one, whichever connection it belongs to. The
+/// pool holds at most a fixed number of idle buffers; buffers returned while
+/// it is full are dropped.
+///
+/// [`PooledJson`]: struct.PooledJson.html
+pub struct BufferPool {
+    buffers: Mutex<Vec<BytesMut>>,
+    max_buffers: usize,
+    capacity: usize,
+}
+
+impl BufferPool {
+    /// Creates a new pool keeping up to `max_buffers` idle buffers, each of
+    /// which reserves room for at least `capacity` bytes before a value is
+    /// serialized into it.
+    ///
+    /// As with [`BufferedJson::with_capacity`], a capacity a bit above the
+    /// size of a typical message lets most values be written without
+    /// reallocating.
+    ///
+    /// [`BufferedJson::with_capacity`]: struct.BufferedJson.html#method.with_capacity
+    pub fn new(max_buffers: usize, capacity: usize) -> Self {
+        BufferPool {
+            buffers: Mutex::new(Vec::with_capacity(max_buffers)),
+            max_buffers,
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of idle buffers kept by the pool.
+    pub fn max_buffers(&self) -> usize {
+        self.max_buffers
+    }
+
+    /// Returns the number of buffers currently idle in the pool.
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<BytesMut>> {
+        // The buffers stay valid even if a thread panicked while holding them
+        self.buffers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn take(&self) -> BytesMut {
+        let mut buf = self.lock().pop().unwrap_or_default();
+        buf.reserve(self.capacity);
+        buf
+    }
+
+    fn put(&self, buf: BytesMut) {
+        let mut buffers = self.lock();
+        if buffers.len() < self.max_buffers {
+            buffers.push(buf);
+        }
+    }
+}
+
+impl fmt::Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("idle", &self.idle())
+            .field("max_buffers", &self.max_buffers)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// JSON serializer drawing its buffers from a [`BufferPool`].
+///
+/// [`BufferedJson`] reuses one buffer per serializer, which stops paying off
+/// when there are many connections sending a few messages each. All
+/// `PooledJson`s created from the same pool share its buffers instead, so
+/// memory freed by the frames of one connection is reused by the others.
+/// Deserialization behaves exactly like [`Json`].
+///
+/// # Examples
+///
+/// ```
+/// use std::{pin::Pin, sync::Arc};
+///
+/// use tokio_serde::Serializer;
+/// use tokio_serde_codecs::{BufferPool, PooledJson};
+///
+/// let pool = Arc::new(BufferPool::new(64, 4096));
+///
+/// let mut first = PooledJson::<Vec<u32>>::new(pool.clone());
+/// let mut second = PooledJson::<Vec<u32>>::new(pool.clone());
+///
+/// let frame = Pin::new(&mut first).serialize(&vec![1, 2, 3]).unwrap();
+/// assert_eq!(frame, &b"[1,2,3]"[..]);
+/// drop(frame);
+///
+/// // The buffer used by `first` is now picked up by `second`
+/// assert_eq!(pool.idle(), 1);
+/// let frame = Pin::new(&mut second).serialize(&vec![4]).unwrap();
+/// assert_eq!(frame, &b"[4]"[..]);
+/// ```
+///
+/// [`BufferPool`]: struct.BufferPool.html
+/// [`BufferedJson`]: struct.BufferedJson.html
+/// [`Json`]: struct.Json.html
+pub struct PooledJson<T> {
+    pool: Arc<BufferPool>,
+    ghost: PhantomData<T>,
+}
+
+impl<T> PooledJson<T> {
+    /// Creates a new JSON serializer and deserializer using the buffers of
+    /// `pool`.
+    pub fn new(pool: Arc<BufferPool>) -> Self {
+        PooledJson {
+            pool,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns the pool the buffers are taken from.
+    pub fn pool(&self) -> &Arc<BufferPool> {
+        &self.pool
+    }
+}
+
+impl<T> Clone for PooledJson<T> {
+    fn clone(&self) -> Self {
+        PooledJson::new(self.pool.clone())
+    }
+}
+
+impl<T> fmt::Debug for PooledJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledJson")
+            .field("pool", &self.pool)
+            .finish()
+    }
+}
+
+impl<T> Deserializer<T> for PooledJson<T>
+where
+    for<'a> T: Deserialize<'a>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        instrument::deserialize("json", src.len(), || from_slice(src))
+    }
+}
+
+impl<T: Serialize> Serializer<T> for PooledJson<T> {
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        instrument::serialize("json", || {
+            let mut buf = self.pool.take();
+            let result = serde_json::to_writer(BytesWriter(&mut buf), item);
+
+            // Split off a partially written value too, so that it doesn't
+            // leak into the next frame
+            let frame = buf.take();
+            self.pool.put(buf);
+
+            result?;
+            Ok(frame.freeze())
+        })
+    }
+}
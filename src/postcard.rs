@@ -0,0 +1,106 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of postcard encoded buffers to a stream of values.
+pub type ReadPostcard<T, U> = FramedRead<T, U, Postcard<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values with postcard.
+pub type WritePostcard<T, U> = FramedWrite<T, U, Postcard<U>>;
+
+/// Postcard serializer and deserializer backed by [postcard].
+///
+/// Values are written with the plain postcard encoding, which does not mark
+/// where a value ends. Postcard can only delimit frames by itself when a
+/// framing flavor such as COBS is used, so pairing this with length-delimited
+/// framing is still recommended.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Postcard;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Reading {
+///     sensor: u8,
+///     millivolts: u16,
+///     label: Option<String>,
+/// }
+///
+/// let reading = Reading {
+///     sensor: 2,
+///     millivolts: 300,
+///     label: None,
+/// };
+///
+/// let mut codec = Postcard::<Reading>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&reading).unwrap();
+/// // Integers are written as varints, and `None` as a single zero byte
+/// assert_eq!(frame, &[0x02, 0xAC, 0x02, 0x00][..]);
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), reading);
+/// ```
+///
+/// [postcard]: https://docs.rs/postcard
+pub struct Postcard<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> Postcard<T> {
+    /// Creates a new postcard serializer and deserializer.
+    pub fn new() -> Self {
+        Postcard { ghost: PhantomData }
+    }
+}
+
+impl<T> Default for Postcard<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Postcard<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Postcard<T> {}
+
+impl<T> fmt::Debug for Postcard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Postcard").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Postcard<T> {
+    const NAME: &'static str = "postcard";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        Ok(postcard::to_allocvec(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(postcard::from_bytes(src)?)
+    }
+}
+
+impl_symmetrical_codec!(Postcard);
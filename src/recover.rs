@@ -0,0 +1,165 @@
+use bytes::BytesMut;
+use futures::{ready, Sink, Stream, TryStream};
+use pin_project::pin_project;
+use tokio_serde::Deserializer;
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::instrument;
+
+/// What to do with frames that fail to deserialize.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Yield the error from the stream, as tokio-serde's `FramedRead` does.
+    #[default]
+    Fail,
+    /// Drop the frame and carry on with the next one.
+    ///
+    /// With the `tracing` feature enabled, every dropped frame is reported
+    /// by an event at the warn level.
+    Skip,
+}
+
+/// Adapts a stream of frames to a stream of values, like tokio-serde's
+/// `FramedRead`, but with a configurable [`ErrorPolicy`].
+///
+/// By default, a frame that cannot be deserialized shows up as an error in
+/// the stream, which usually means that the stream is given up on. With
+/// [`ErrorPolicy::Skip`], such frames are dropped instead, so that a single
+/// corrupt message does not end e.g. a long-lived telemetry feed. Errors of
+/// the underlying stream are always passed on, as the framing may be lost
+/// after them. Like the adaptors of tokio-serde, this forwards `Sink` calls
+/// to the wrapped transport.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use futures::{executor::block_on, stream, TryStreamExt};
+/// use tokio_serde_codecs::{ErrorPolicy, Json, Recovering};
+///
+/// let frames = stream::iter(vec![
+///     Ok::<_, std::io::Error>(BytesMut::from(&b"1"[..])),
+///     Ok(BytesMut::from(&b"{oops"[..])),
+///     Ok(BytesMut::from(&b"2"[..])),
+/// ]);
+///
+/// let values = Recovering::new(frames, Json::<i32>::new()).on_error(ErrorPolicy::Skip);
+///
+/// assert_eq!(block_on(values.try_collect::<Vec<_>>()).unwrap(), vec![1, 2]);
+/// ```
+///
+/// [`ErrorPolicy`]: enum.ErrorPolicy.html
+/// [`ErrorPolicy::Skip`]: enum.ErrorPolicy.html#variant.Skip
+#[pin_project]
+pub struct Recovering<T, U, C> {
+    #[pin]
+    inner: T,
+    #[pin]
+    deserializer: C,
+    policy: ErrorPolicy,
+    ghost: PhantomData<U>,
+}
+
+impl<T, U, C> Recovering<T, U, C> {
+    /// Creates a new adaptor deserializing the frames of `inner` with
+    /// `deserializer`, failing on the first frame that cannot be
+    /// deserialized.
+    pub fn new(inner: T, deserializer: C) -> Self {
+        Recovering {
+            inner,
+            deserializer,
+            policy: ErrorPolicy::Fail,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets what to do with frames that fail to deserialize.
+    pub fn on_error(mut self, policy: ErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes the adaptor, returning the underlying stream.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: fmt::Debug, U, C: fmt::Debug> fmt::Debug for Recovering<T, U, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recovering")
+            .field("inner", &self.inner)
+            .field("deserializer", &self.deserializer)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl<T, U, C> Stream for Recovering<T, U, C>
+where
+    T: TryStream<Ok = BytesMut>,
+    T::Error: From<C::Error>,
+    C: Deserializer<U>,
+    C::Error: fmt::Display,
+{
+    type Item = Result<U, T::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let frame = match ready!(this.inner.as_mut().try_poll_next(cx)) {
+                Some(Ok(frame)) => frame,
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            };
+
+            match this.deserializer.as_mut().deserialize(&frame) {
+                Ok(value) => return Poll::Ready(Some(Ok(value))),
+                Err(err) if *this.policy == ErrorPolicy::Skip => {
+                    instrument::skipped(frame.len(), &err)
+                }
+                Err(err) => return Poll::Ready(Some(Err(err.into()))),
+            }
+        }
+    }
+}
+
+impl<T, U, C, SinkItem> Sink<SinkItem> for Recovering<T, U, C>
+where
+    T: Sink<SinkItem>,
+{
+    type Error = T::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: SinkItem) -> Result<(), Self::Error> {
+        self.project().inner.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
@@ -0,0 +1,144 @@
+use bytes::Bytes;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of RON encoded buffers to a stream of values.
+pub type ReadRon<T, U> = FramedRead<T, U, Ron<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as RON.
+pub type WriteRon<T, U> = FramedWrite<T, U, Ron<U>>;
+
+/// RON serializer and deserializer backed by [ron].
+///
+/// The Rusty Object Notation writes values much like Rust source does: enum
+/// variants appear by name, with their fields in parentheses, and structs
+/// and tuples look like their Rust literals. This makes frames easy to read
+/// and edit by hand, e.g. in developer tooling. Values are written on a
+/// single line unless [`pretty`] output has been configured.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Ron;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Command {
+///     Stop,
+///     Move { x: i32, y: i32 },
+/// }
+///
+/// let mut codec = Ron::<Command>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&Command::Stop).unwrap();
+/// assert_eq!(frame, &b"Stop"[..]);
+///
+/// let command = Command::Move { x: 3, y: -1 };
+/// let frame = Pin::new(&mut codec).serialize(&command).unwrap();
+/// assert_eq!(frame, &b"Move(x:3,y:-1)"[..]);
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), command);
+/// ```
+///
+/// [ron]: https://docs.rs/ron
+/// [`pretty`]: #method.pretty
+pub struct Ron<T> {
+    pretty: Option<PrettyConfig>,
+    ghost: PhantomData<T>,
+}
+
+impl<T> Ron<T> {
+    /// Creates a new RON serializer and deserializer.
+    pub fn new() -> Self {
+        Ron {
+            pretty: None,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Makes the serializer write values over several indented lines, as
+    /// configured by `config`.
+    ///
+    /// The deserializer accepts both compact and pretty frames either way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::Pin;
+    ///
+    /// use ron::ser::PrettyConfig;
+    /// use tokio_serde::{Deserializer, Serializer};
+    /// use tokio_serde_codecs::Ron;
+    ///
+    /// let mut codec = Ron::<Vec<(String, u32)>>::new().pretty(PrettyConfig::new());
+    ///
+    /// let value = vec![("a".to_owned(), 1), ("b".to_owned(), 2)];
+    /// let frame = Pin::new(&mut codec).serialize(&value).unwrap();
+    /// assert_eq!(
+    ///     std::str::from_utf8(&frame).unwrap(),
+    ///     "[\n    (\"a\", 1),\n    (\"b\", 2),\n]",
+    /// );
+    ///
+    /// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), value);
+    /// ```
+    pub fn pretty(mut self, config: PrettyConfig) -> Self {
+        self.pretty = Some(config);
+        self
+    }
+}
+
+impl<T> Default for Ron<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Ron<T> {
+    fn clone(&self) -> Self {
+        Ron {
+            pretty: self.pretty.clone(),
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Ron<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ron").field("pretty", &self.pretty).finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Ron<T> {
+    const NAME: &'static str = "ron";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        let text = match self.pretty {
+            Some(ref config) => ron::ser::to_string_pretty(item, config.clone())?,
+            None => ron::ser::to_string(item)?,
+        };
+        Ok(text.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(ron::de::from_bytes(src)?)
+    }
+}
+
+impl_symmetrical_codec!(Ron);
@@ -0,0 +1,148 @@
+// Sorting of object keys in serialized JSON.
+//
+// Keys are sorted after serializing, by rearranging the members of every
+// object in the output. Whitespace and separators stay in place, so this
+// works for compact and indented output alike, as well as with the literals
+// of `NumberPolicy::Literal`. The input is always produced by serde_json, so
+// it is well-formed and the scan does not need to validate it.
+
+/// Returns `src` with the members of every object sorted by key.
+pub(crate) fn sort_keys(src: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(src.len());
+
+    let start = whitespace(src, 0);
+    out.extend_from_slice(&src[..start]);
+    let end = value(src, start, &mut out);
+    out.extend_from_slice(&src[end..]);
+
+    out
+}
+
+/// Returns the position of the first non-whitespace byte at or after `pos`.
+fn whitespace(src: &[u8], mut pos: usize) -> usize {
+    while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = src.get(pos) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Copies the value starting at `pos` to `out`, and returns the position
+/// after it.
+fn value(src: &[u8], pos: usize, out: &mut Vec<u8>) -> usize {
+    match src.get(pos) {
+        Some(b'{') => object(src, pos, out),
+        Some(b'[') => array(src, pos, out),
+        Some(b'"') => {
+            let end = string(src, pos);
+            out.extend_from_slice(&src[pos..end]);
+            end
+        }
+        Some(_) => {
+            // Numbers and literals; always consume at least one byte
+            let len = src[pos + 1..]
+                .iter()
+                .position(|b| b",:]} \t\n\r".contains(b))
+                .map_or(src.len() - pos, |len| len + 1);
+            out.extend_from_slice(&src[pos..pos + len]);
+            pos + len
+        }
+        None => pos,
+    }
+}
+
+/// Returns the position after the string starting at `pos`.
+fn string(src: &[u8], pos: usize) -> usize {
+    let mut escaped = false;
+
+    for (i, &b) in src.iter().enumerate().skip(pos + 1) {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return i + 1,
+            _ => {}
+        }
+    }
+
+    src.len()
+}
+
+fn array(src: &[u8], mut pos: usize, out: &mut Vec<u8>) -> usize {
+    out.push(b'[');
+    pos += 1;
+
+    loop {
+        let end = whitespace(src, pos);
+        out.extend_from_slice(&src[pos..end]);
+        pos = end;
+
+        match src.get(pos) {
+            Some(b']') => {
+                out.push(b']');
+                return pos + 1;
+            }
+            Some(b',') => {
+                out.push(b',');
+                pos += 1;
+            }
+            Some(_) => pos = value(src, pos, out),
+            None => return pos,
+        }
+    }
+}
+
+fn object(src: &[u8], mut pos: usize, out: &mut Vec<u8>) -> usize {
+    // Whitespace and separators before, between and after the members,
+    // which stay where they are while the members are rearranged
+    let mut gaps = Vec::new();
+    let mut members = Vec::new();
+    pos += 1;
+
+    loop {
+        let start = pos;
+        pos = whitespace(src, pos);
+        if src.get(pos) == Some(&b',') {
+            pos = whitespace(src, pos + 1);
+        }
+        gaps.push(&src[start..pos]);
+
+        match src.get(pos) {
+            Some(b'}') | None => break,
+            Some(_) => {
+                let mut member = Vec::new();
+                let key_end = value(src, pos, &mut member);
+                let key = key(&src[pos..key_end]);
+
+                let value_start = src[key_end..]
+                    .iter()
+                    .position(|b| !b": \t\n\r".contains(b))
+                    .map_or(src.len(), |len| key_end + len);
+                member.extend_from_slice(&src[key_end..value_start]);
+
+                pos = value(src, value_start, &mut member);
+                members.push((key, member));
+            }
+        }
+    }
+
+    members.sort_by(|a, b| a.0.cmp(&b.0));
+
+    out.push(b'{');
+    for (gap, (_, member)) in gaps.iter().zip(&members) {
+        out.extend_from_slice(gap);
+        out.extend_from_slice(member);
+    }
+    out.extend_from_slice(gaps[members.len()]);
+
+    if pos < src.len() {
+        out.push(b'}');
+        pos += 1;
+    }
+
+    pos
+}
+
+/// Decodes a key, so that keys are compared by their text rather than by
+/// their escaped form.
+fn key(src: &[u8]) -> String {
+    serde_json::from_slice(src).unwrap_or_else(|_| String::from_utf8_lossy(src).into_owned())
+}
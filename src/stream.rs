@@ -0,0 +1,163 @@
+use bytes::BytesMut;
+use futures::Stream;
+use pin_project::pin_project;
+use serde::de::DeserializeOwned;
+use tokio_io::AsyncRead;
+
+use std::{
+    fmt, io,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Number of bytes read from the reader at once.
+const CHUNK_LEN: usize = 8 * 1024;
+
+/// Stream of JSON values read from a sequence of concatenated values without
+/// any framing.
+///
+/// The bytes read from `R` are buffered until they hold a complete value,
+/// which is then deserialized and yielded, while the bytes following it are
+/// kept for the next one. Values may be separated by whitespace, e.g. one
+/// per line, but need not be, as in `{"a":1}{"a":2}`. This is the same input
+/// as accepted by `serde_json::StreamDeserializer`, read from an `AsyncRead`
+/// such as a file or socket.
+///
+/// Unlike [`ConcatJson`], values of any type may follow each other, but
+/// every value has to be parsed again from the start when a read only
+/// delivers part of it. The stream ends when the reader does, or after the
+/// first error, as the input cannot be resynchronized after invalid JSON.
+///
+/// # Examples
+///
+/// ```
+/// use futures::{executor, prelude::*};
+/// use tokio_serde_codecs::JsonStream;
+///
+/// let input: &[u8] = b"{\"id\":1} {\"id\":2}\n[3]";
+///
+/// let values: Vec<serde_json::Value> =
+///     executor::block_on(JsonStream::new(input).try_collect()).unwrap();
+/// assert_eq!(values.len(), 3);
+/// assert_eq!(values[2], serde_json::json!([3]));
+/// ```
+///
+/// [`ConcatJson`]: struct.ConcatJson.html
+#[pin_project]
+pub struct JsonStream<R, T> {
+    #[pin]
+    reader: R,
+    buf: BytesMut,
+    max_len: Option<usize>,
+    done: bool,
+    ghost: PhantomData<fn() -> T>,
+}
+
+impl<R, T> JsonStream<R, T> {
+    /// Creates a new stream of the JSON values read from `reader`.
+    pub fn new(reader: R) -> Self {
+        JsonStream {
+            reader,
+            buf: BytesMut::new(),
+            max_len: None,
+            done: false,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length in bytes of a single value.
+    ///
+    /// A value growing longer than this is reported as an error instead of
+    /// being buffered further. There is no limit by default.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the stream, returning the underlying reader.
+    ///
+    /// Bytes which have already been read but not yet deserialized are
+    /// lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: fmt::Debug, T> fmt::Debug for JsonStream<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonStream")
+            .field("reader", &self.reader)
+            .field("buffered", &self.buf.len())
+            .field("max_len", &self.max_len)
+            .finish()
+    }
+}
+
+impl<R, T> Stream for JsonStream<R, T>
+where
+    R: AsyncRead,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let mut values = serde_json::Deserializer::from_slice(this.buf).into_iter::<T>();
+            let value = values.next();
+            let len = values.byte_offset();
+
+            match value {
+                // A number at the very end may continue in the next read
+                Some(Ok(_))
+                    if !*this.done
+                        && len == this.buf.len()
+                        && this.buf[len - 1].is_ascii_alphanumeric() => {}
+                Some(Ok(value)) => {
+                    this.buf.advance(len);
+                    return Poll::Ready(Some(Ok(value)));
+                }
+                Some(Err(ref err)) if err.is_eof() && !*this.done => {}
+                Some(Err(err)) => {
+                    this.buf.clear();
+                    *this.done = true;
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+                None if *this.done => return Poll::Ready(None),
+                // Only whitespace so far
+                None => this.buf.clear(),
+            }
+
+            if let Some(max_len) = *this.max_len {
+                if this.buf.len() > max_len {
+                    this.buf.clear();
+                    *this.done = true;
+                    return Poll::Ready(Some(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("value exceeds maximum length of {} bytes", max_len),
+                    ))));
+                }
+            }
+
+            let mut chunk = [0; CHUNK_LEN];
+            match this.reader.as_mut().poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => *this.done = true,
+                Poll::Ready(Ok(len)) => this.buf.extend_from_slice(&chunk[..len]),
+                Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use bytes::Bytes;
+use serde::{
+    de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor},
+    ser::{self, Impossible},
+    Deserialize, Serialize,
+};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError, JsonError,
+};
+
+/// Serializer and deserializer for enums, identifying the variant by a
+/// leading tag byte.
+///
+/// Every frame starts with a single byte holding the index of the variant,
+/// followed by the contents of the variant as compact JSON: the value of a
+/// newtype variant, an array of the fields of a tuple variant, an object of
+/// the fields of a struct variant, and nothing for a unit variant. This
+/// allows multiplexing several kinds of messages over one transport with an
+/// overhead of one byte each, instead of an externally tagged JSON object.
+///
+/// The tag is the position of the variant in the declaration of the enum,
+/// as used by serde, so both peers must declare the variants in the same
+/// order. Only enums with up to 256 variants can be serialized, and values
+/// of other types fail to serialize.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Tagged;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Message {
+///     Ping,
+///     Say(String),
+///     Move { x: i32, y: i32 },
+/// }
+///
+/// let mut codec = Tagged::<Message>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&Message::Say("hi".into())).unwrap();
+/// assert_eq!(frame, &b"\x01\"hi\""[..]);
+///
+/// for message in vec![Message::Ping, Message::Say("hi".into()), Message::Move { x: 1, y: -1 }] {
+///     let frame = Pin::new(&mut codec).serialize(&message).unwrap();
+///     let decoded = Pin::new(&mut codec).deserialize(&frame.into()).unwrap();
+///     assert_eq!(decoded, message);
+/// }
+/// ```
+pub struct Tagged<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> Tagged<T> {
+    /// Creates a new tagged serializer and deserializer.
+    pub fn new() -> Self {
+        Tagged { ghost: PhantomData }
+    }
+}
+
+impl<T> Default for Tagged<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Tagged<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Tagged<T> {}
+
+impl<T> fmt::Debug for Tagged<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tagged").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Tagged<T> {
+    const NAME: &'static str = "tagged";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        let mut buf = Vec::new();
+        item.serialize(TagSerializer { buf: &mut buf })?;
+        Ok(buf.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        let (&tag, contents) = match src.split_first() {
+            Some(split) => split,
+            None => {
+                let err = de::Error::custom("missing tag byte");
+                return Err(JsonError::new(err, src).into());
+            }
+        };
+
+        T::deserialize(TagDeserializer { tag, contents })
+            .map_err(|err| JsonError::new(err, src).into())
+    }
+}
+
+impl_symmetrical_codec!(Tagged);
+
+/// Serializer writing the tag and contents of an enum variant.
+struct TagSerializer<'a> {
+    buf: &'a mut Vec<u8>,
+}
+
+impl<'a> TagSerializer<'a> {
+    fn write_tag(&mut self, index: u32) -> Result<(), serde_json::Error> {
+        if index > u32::from(u8::MAX) {
+            return Err(ser::Error::custom(format_args!(
+                "variant index {} does not fit into a tag byte",
+                index
+            )));
+        }
+
+        self.buf.push(index as u8);
+        Ok(())
+    }
+
+    fn compound(mut self, index: u32, open: u8) -> Result<Compound<'a>, serde_json::Error> {
+        self.write_tag(index)?;
+        self.buf.push(open);
+
+        Ok(Compound {
+            buf: self.buf,
+            first: true,
+        })
+    }
+}
+
+fn not_an_enum() -> serde_json::Error {
+    ser::Error::custom("only enums can be serialized with a tag")
+}
+
+macro_rules! reject_serialize {
+    ($($method:ident($($ty:ty),*),)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<(), serde_json::Error> {
+                Err(not_an_enum())
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for TagSerializer<'a> {
+    type Ok = ();
+    type Error = serde_json::Error;
+    type SerializeSeq = Impossible<(), serde_json::Error>;
+    type SerializeTuple = Impossible<(), serde_json::Error>;
+    type SerializeTupleStruct = Impossible<(), serde_json::Error>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Impossible<(), serde_json::Error>;
+    type SerializeStruct = Impossible<(), serde_json::Error>;
+    type SerializeStructVariant = Compound<'a>;
+
+    reject_serialize! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<(), serde_json::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_variant(
+        mut self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+    ) -> Result<(), serde_json::Error> {
+        self.write_tag(index)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        mut self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        self.write_tag(index)?;
+        serde_json::to_writer(&mut *self.buf, value)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, serde_json::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, serde_json::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, serde_json::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>, serde_json::Error> {
+        self.compound(index, b'[')
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, serde_json::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, serde_json::Error> {
+        Err(not_an_enum())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>, serde_json::Error> {
+        self.compound(index, b'{')
+    }
+}
+
+/// Writer of the JSON array or object holding the fields of a variant.
+struct Compound<'a> {
+    buf: &'a mut Vec<u8>,
+    first: bool,
+}
+
+impl Compound<'_> {
+    fn separate(&mut self) {
+        if !self.first {
+            self.buf.push(b',');
+        }
+        self.first = false;
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        self.separate();
+        serde_json::to_writer(&mut *self.buf, value)
+    }
+
+    fn end(self) -> Result<(), serde_json::Error> {
+        self.buf.push(b']');
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        self.separate();
+        serde_json::to_writer(&mut *self.buf, key)?;
+        self.buf.push(b':');
+        serde_json::to_writer(&mut *self.buf, value)
+    }
+
+    fn end(self) -> Result<(), serde_json::Error> {
+        self.buf.push(b'}');
+        Ok(())
+    }
+}
+
+/// Deserializer reading an enum variant from its tag and contents.
+struct TagDeserializer<'de> {
+    tag: u8,
+    contents: &'de [u8],
+}
+
+impl<'de> TagDeserializer<'de> {
+    /// Parses the contents of the variant with `f`, which must consume all
+    /// of them.
+    fn parse<T, F>(self, f: F) -> Result<T, serde_json::Error>
+    where
+        F: FnOnce(
+            &mut serde_json::Deserializer<serde_json::de::SliceRead<'de>>,
+        ) -> Result<T, serde_json::Error>,
+    {
+        let mut deserializer = serde_json::Deserializer::from_slice(self.contents);
+        let value = f(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for TagDeserializer<'de> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, serde_json::Error> {
+        Err(de::Error::custom(
+            "only enums can be deserialized from a tag",
+        ))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, serde_json::Error> {
+        visitor.visit_enum(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> EnumAccess<'de> for TagDeserializer<'de> {
+    type Error = serde_json::Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), serde_json::Error> {
+        let index: de::value::U32Deserializer<serde_json::Error> =
+            u32::from(self.tag).into_deserializer();
+        Ok((seed.deserialize(index)?, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for TagDeserializer<'de> {
+    type Error = serde_json::Error;
+
+    fn unit_variant(self) -> Result<(), serde_json::Error> {
+        if self.contents.is_empty() {
+            Ok(())
+        } else {
+            Err(de::Error::custom("trailing data after unit variant tag"))
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, serde_json::Error> {
+        self.parse(|deserializer| seed.deserialize(deserializer))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, serde_json::Error> {
+        self.parse(|deserializer| de::Deserializer::deserialize_tuple(deserializer, len, visitor))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, serde_json::Error> {
+        self.parse(|deserializer| {
+            de::Deserializer::deserialize_struct(deserializer, "", fields, visitor)
+        })
+    }
+}
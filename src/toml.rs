@@ -0,0 +1,295 @@
+use bytes::Bytes;
+use serde::{
+    ser::{self, Impossible},
+    Deserialize, Serialize, Serializer,
+};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{error, fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of TOML encoded buffers to a stream of values.
+pub type ReadToml<T, U> = FramedRead<T, U, Toml<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as TOML.
+pub type WriteToml<T, U> = FramedWrite<T, U, Toml<U>>;
+
+/// TOML serializer and deserializer backed by [toml].
+///
+/// A TOML document is always a table, so only map-like values (structs and
+/// maps) can be sent. Serializing a top-level sequence or scalar fails with
+/// a [`toml::ser::Error`], wrapped in [`CodecError::TomlEncode`].
+///
+/// # Examples
+///
+/// ```
+/// use std::{collections::BTreeMap, pin::Pin};
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Toml;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Database {
+///     url: String,
+///     pool: u32,
+/// }
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Settings {
+///     name: String,
+///     database: Database,
+///     limits: BTreeMap<String, u32>,
+/// }
+///
+/// let mut limits = BTreeMap::new();
+/// limits.insert("connections".to_owned(), 100);
+///
+/// let settings = Settings {
+///     name: "api".into(),
+///     database: Database { url: "postgres://db".into(), pool: 8 },
+///     limits,
+/// };
+///
+/// let mut codec = Toml::<Settings>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&settings).unwrap();
+/// assert_eq!(
+///     std::str::from_utf8(&frame).unwrap(),
+///     "name = \"api\"\n\
+///      \n\
+///      [database]\n\
+///      url = \"postgres://db\"\n\
+///      pool = 8\n\
+///      \n\
+///      [limits]\n\
+///      connections = 100\n",
+/// );
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), settings);
+/// ```
+///
+/// Values which are not tables are rejected:
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use tokio_serde::Serializer;
+/// use tokio_serde_codecs::{CodecError, Toml};
+///
+/// match Pin::new(&mut Toml::new()).serialize(&vec![1, 2, 3]) {
+///     Err(CodecError::TomlEncode(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+///
+/// match Pin::new(&mut Toml::new()).serialize(&5) {
+///     Err(CodecError::TomlEncode(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [toml]: https://docs.rs/toml
+/// [`toml::ser::Error`]: https://docs.rs/toml/0.5/toml/ser/enum.Error.html
+/// [`CodecError::TomlEncode`]: enum.CodecError.html#variant.TomlEncode
+pub struct Toml<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> Toml<T> {
+    /// Creates a new TOML serializer and deserializer.
+    pub fn new() -> Self {
+        Toml { ghost: PhantomData }
+    }
+}
+
+impl<T> Default for Toml<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Toml<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Toml<T> {}
+
+impl<T> fmt::Debug for Toml<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Toml").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Toml<T> {
+    const NAME: &'static str = "toml";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        // The `toml` crate writes top-level sequences and scalars as bare
+        // values, which are not TOML documents
+        if !is_table(item) {
+            return Err(<toml::ser::Error as ser::Error>::custom(
+                "only tables can be written as TOML documents",
+            )
+            .into());
+        }
+
+        Ok(toml::to_string(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(toml::from_slice(src)?)
+    }
+}
+
+impl_symmetrical_codec!(Toml);
+
+/// Returns whether `item` serializes as a table, without serializing it.
+fn is_table<T: ?Sized + Serialize>(item: &T) -> bool {
+    match item.serialize(TopLevel) {
+        Err(IsTable(is_table)) => is_table,
+        Ok(()) => false,
+    }
+}
+
+/// Outcome of looking at the top-level value. It is returned as the error of
+/// [`TopLevel`], which stops right after the first call.
+#[derive(Debug)]
+struct IsTable(bool);
+
+impl fmt::Display for IsTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0 { "table" } else { "not a table" })
+    }
+}
+
+impl error::Error for IsTable {}
+
+impl ser::Error for IsTable {
+    fn custom<M: fmt::Display>(_: M) -> Self {
+        IsTable(false)
+    }
+}
+
+/// Serializer telling tables apart from other top-level values.
+struct TopLevel;
+
+macro_rules! not_a_table {
+    ($($method:ident($($ty:ty),*),)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<(), IsTable> {
+                Err(IsTable(false))
+            }
+        )*
+    };
+}
+
+impl Serializer for TopLevel {
+    type Ok = ();
+    type Error = IsTable;
+    type SerializeSeq = Impossible<(), IsTable>;
+    type SerializeTuple = Impossible<(), IsTable>;
+    type SerializeTupleStruct = Impossible<(), IsTable>;
+    type SerializeTupleVariant = Impossible<(), IsTable>;
+    type SerializeMap = Impossible<(), IsTable>;
+    type SerializeStruct = Impossible<(), IsTable>;
+    type SerializeStructVariant = Impossible<(), IsTable>;
+
+    not_a_table! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), IsTable> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), IsTable> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), IsTable> {
+        Err(IsTable(false))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, IsTable> {
+        Err(IsTable(false))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, IsTable> {
+        Err(IsTable(false))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, IsTable> {
+        Err(IsTable(false))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, IsTable> {
+        Err(IsTable(false))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, IsTable> {
+        Err(IsTable(true))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, IsTable> {
+        Err(IsTable(true))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, IsTable> {
+        Err(IsTable(false))
+    }
+}
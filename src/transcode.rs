@@ -0,0 +1,98 @@
+use bytes::{Bytes, BytesMut};
+
+use crate::CodecError;
+
+/// Converts a frame holding a JSON value into one holding the same value as
+/// MessagePack.
+///
+/// The value is transcoded with [serde-transcode], directly from the JSON
+/// parser into the MessagePack serializer, so no intermediate value or
+/// concrete type is needed. This allows bridging a JSON producer and a
+/// MessagePack consumer, e.g. in a proxy. Only whitespace may follow the
+/// JSON value.
+///
+/// Errors of both the parser and the serializer are reported as
+/// [`CodecError::MessagePackEncode`], except for trailing data, which is
+/// reported as [`CodecError::Json`].
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use serde_json::{json, Value};
+/// use tokio_serde_codecs::{transcode_json_to_msgpack, CodecError};
+///
+/// let value = json!({"name": "John Doe", "phones": [{"home": "+44 1234567"}], "age": 43});
+/// let frame = BytesMut::from(serde_json::to_vec(&value).unwrap());
+///
+/// let msgpack = transcode_json_to_msgpack(&frame).unwrap();
+/// assert_eq!(rmp_serde::from_slice::<Value>(&msgpack).unwrap(), value);
+///
+/// let frame = BytesMut::from(&b"[1] [2]"[..]);
+/// match transcode_json_to_msgpack(&frame) {
+///     Err(CodecError::Json(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [serde-transcode]: https://docs.rs/serde-transcode
+/// [`CodecError::MessagePackEncode`]: enum.CodecError.html#variant.MessagePackEncode
+/// [`CodecError::Json`]: enum.CodecError.html#variant.Json
+pub fn transcode_json_to_msgpack(src: &BytesMut) -> Result<Bytes, CodecError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(src);
+    let mut buf = Vec::new();
+
+    serde_transcode::transcode(&mut deserializer, &mut rmp_serde::Serializer::new(&mut buf))?;
+    deserializer.end()?;
+
+    Ok(buf.into())
+}
+
+/// Converts a frame holding a MessagePack value into one holding the same
+/// value as compact JSON.
+///
+/// This is the reverse of [`transcode_json_to_msgpack`]. Nothing may follow
+/// the MessagePack value. Errors of both the parser and the serializer are
+/// reported as [`CodecError::Json`], except for trailing data, which is
+/// reported as [`CodecError::MessagePackDecode`]. As JSON object keys are
+/// always strings, maps with keys of other types cannot be transcoded.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use tokio_serde_codecs::{transcode_msgpack_to_json, CodecError};
+///
+/// let frame = BytesMut::from(&[0x92, 0x01, 0xA1, b'a'][..]);
+/// assert_eq!(transcode_msgpack_to_json(&frame).unwrap(), &br#"[1,"a"]"#[..]);
+///
+/// let frame = BytesMut::from(&[0x92, 0x01, 0xA1, b'a', 0x02][..]);
+/// match transcode_msgpack_to_json(&frame) {
+///     Err(CodecError::MessagePackDecode(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [`transcode_json_to_msgpack`]: fn.transcode_json_to_msgpack.html
+/// [`CodecError::Json`]: enum.CodecError.html#variant.Json
+/// [`CodecError::MessagePackDecode`]: enum.CodecError.html#variant.MessagePackDecode
+pub fn transcode_msgpack_to_json(src: &BytesMut) -> Result<Bytes, CodecError> {
+    let mut rest = &src[..];
+    let mut buf = Vec::new();
+
+    serde_transcode::transcode(
+        &mut rmp_serde::Deserializer::new(&mut rest),
+        &mut serde_json::Serializer::new(&mut buf),
+    )?;
+
+    // Like the JSON side, reject anything following the value
+    if !rest.is_empty() {
+        return Err(rmp_serde::decode::Error::Syntax(format!(
+            "{} unexpected bytes after the value",
+            rest.len()
+        ))
+        .into());
+    }
+
+    Ok(buf.into())
+}
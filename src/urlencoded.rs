@@ -0,0 +1,161 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of URL encoded buffers to a stream of values.
+pub type ReadUrlEncoded<T, U> = FramedRead<T, U, UrlEncoded<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as URL encoded
+/// forms.
+pub type WriteUrlEncoded<T, U> = FramedWrite<T, U, UrlEncoded<U>>;
+
+/// Serializer and deserializer of `application/x-www-form-urlencoded`
+/// frames backed by [serde_urlencoded].
+///
+/// A form is a flat list of key/value pairs, so only structs and maps whose
+/// values are scalars, strings or options can be encoded. Values holding
+/// nested structs, maps or sequences fail to serialize and deserialize with
+/// a [`CodecError::UrlEncodedEncode`] or [`CodecError::UrlEncodedDecode`].
+/// Following the convention of serde_urlencoded, a key repeated for several
+/// values is written from, and read back into, a sequence of key/value
+/// pairs such as `Vec<(String, String)>`, rather than a field holding a
+/// sequence.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::UrlEncoded;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Login {
+///     user: String,
+///     remember: bool,
+///     attempts: u32,
+/// }
+///
+/// let login = Login {
+///     user: "jane doe".into(),
+///     remember: true,
+///     attempts: 2,
+/// };
+///
+/// let mut codec = UrlEncoded::<Login>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&login).unwrap();
+/// assert_eq!(frame, &b"user=jane+doe&remember=true&attempts=2"[..]);
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), login);
+/// ```
+///
+/// Nested values are rejected:
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::Serialize;
+/// use tokio_serde::Serializer;
+/// use tokio_serde_codecs::{CodecError, UrlEncoded};
+///
+/// #[derive(Serialize)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Customer {
+///     name: String,
+///     address: Address,
+/// }
+///
+/// let customer = Customer {
+///     name: "Jane".into(),
+///     address: Address { city: "Oslo".into() },
+/// };
+///
+/// match Pin::new(&mut UrlEncoded::new()).serialize(&customer) {
+///     Err(CodecError::UrlEncodedEncode(_)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// Repeated keys are read and written as a sequence of pairs:
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::UrlEncoded;
+///
+/// let mut codec = UrlEncoded::<Vec<(String, u32)>>::new();
+///
+/// let tags = vec![("tag".to_owned(), 1), ("tag".to_owned(), 2)];
+/// let frame = Pin::new(&mut codec).serialize(&tags).unwrap();
+/// assert_eq!(frame, &b"tag=1&tag=2"[..]);
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), tags);
+/// ```
+///
+/// [serde_urlencoded]: https://docs.rs/serde_urlencoded
+/// [`CodecError::UrlEncodedEncode`]: enum.CodecError.html#variant.UrlEncodedEncode
+/// [`CodecError::UrlEncodedDecode`]: enum.CodecError.html#variant.UrlEncodedDecode
+pub struct UrlEncoded<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> UrlEncoded<T> {
+    /// Creates a new URL encoded form serializer and deserializer.
+    pub fn new() -> Self {
+        UrlEncoded { ghost: PhantomData }
+    }
+}
+
+impl<T> Default for UrlEncoded<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for UrlEncoded<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for UrlEncoded<T> {}
+
+impl<T> fmt::Debug for UrlEncoded<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UrlEncoded").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for UrlEncoded<T> {
+    const NAME: &'static str = "urlencoded";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        Ok(serde_urlencoded::to_string(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(serde_urlencoded::from_bytes(src)?)
+    }
+}
+
+impl_symmetrical_codec!(UrlEncoded);
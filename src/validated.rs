@@ -0,0 +1,115 @@
+use bytes::Bytes;
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    json::from_slice,
+    CodecError, JsonError, SchemaViolation,
+};
+
+/// JSON serializer and deserializer validating inbound values against a
+/// [JSON Schema] before deserializing them.
+///
+/// Every frame is first parsed into a `serde_json::Value`, which is checked
+/// against the schema using [jsonschema]. Frames which do not conform are
+/// rejected with a [`SchemaViolation`] listing every failing location of
+/// the value, and only the remaining ones are deserialized into `T`. This
+/// catches messages that a lenient `Deserialize` impl would otherwise
+/// accept, e.g. ones breaking constraints the type itself cannot express.
+/// Serialization behaves exactly like [`Json`]; outbound values are not
+/// validated.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use jsonschema::JSONSchema;
+/// use serde_json::{json, Value};
+/// use tokio_serde::Deserializer;
+/// use tokio_serde_codecs::{CodecError, ValidatedJson};
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": { "name": { "type": "string" } },
+///     "required": ["name"],
+/// });
+/// let schema = JSONSchema::compile(&schema).unwrap();
+/// let mut json = ValidatedJson::<Value>::new(schema);
+///
+/// let frame = b"{\"name\":\"John Doe\"}"[..].into();
+/// assert!(Pin::new(&mut json).deserialize(&frame).is_ok());
+///
+/// let frame = b"{\"age\":43}"[..].into();
+/// match Pin::new(&mut json).deserialize(&frame) {
+///     Err(CodecError::Schema(err)) => {
+///         assert!(err.to_string().contains("\"name\" is a required property"));
+///     }
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [JSON Schema]: https://json-schema.org
+/// [jsonschema]: https://docs.rs/jsonschema
+/// [`SchemaViolation`]: struct.SchemaViolation.html
+/// [`Json`]: struct.Json.html
+pub struct ValidatedJson<T> {
+    schema: JSONSchema,
+    ghost: PhantomData<T>,
+}
+
+impl<T> ValidatedJson<T> {
+    /// Creates a new JSON serializer and deserializer rejecting inbound
+    /// values which do not conform to `schema`.
+    pub fn new(schema: JSONSchema) -> Self {
+        ValidatedJson {
+            schema,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns the schema inbound values are validated against.
+    pub fn schema(&self) -> &JSONSchema {
+        &self.schema
+    }
+}
+
+impl<T> fmt::Debug for ValidatedJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidatedJson").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for ValidatedJson<T> {
+    const NAME: &'static str = "json";
+    const JSON: bool = true;
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        Ok(serde_json::to_vec(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        let value: Value = from_slice(src)?;
+
+        if let Err(errors) = self.schema.validate(&value) {
+            let errors = errors
+                .map(|err| (err.instance_path.to_string(), err.to_string()))
+                .collect();
+            return Err(SchemaViolation { errors }.into());
+        }
+
+        T::deserialize(value).map_err(|err| JsonError::new(err, src).into())
+    }
+}
+
+impl_symmetrical_codec!(ValidatedJson);
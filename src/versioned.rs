@@ -0,0 +1,128 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use pin_project::pin_project;
+use tokio_serde::{Deserializer, Serializer};
+
+use std::pin::Pin;
+
+use crate::{CodecError, VersionMismatch};
+
+/// Serializer and deserializer prefixing the frames of another one with a
+/// schema version byte.
+///
+/// Every serialized frame starts with the configured version, followed by
+/// the frame of the inner serializer. When deserializing, the version of
+/// each frame is checked against the configured one before the rest is
+/// passed to the inner deserializer. Frames of any other version are
+/// rejected with a [`VersionMismatch`] error, so that peers speaking
+/// incompatible revisions of a protocol notice instead of misinterpreting
+/// each other's messages.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::{CodecError, Json, Versioned};
+///
+/// let mut v1 = Versioned::new(Json::<i32>::new(), 1);
+/// let mut v2 = Versioned::new(Json::<i32>::new(), 2);
+///
+/// let frame = Pin::new(&mut v1).serialize(&42).unwrap();
+/// assert_eq!(frame, &b"\x0142"[..]);
+///
+/// let value = Pin::new(&mut v1).deserialize(&frame.clone().into()).unwrap();
+/// assert_eq!(value, 42);
+///
+/// match Pin::new(&mut v2).deserialize(&frame.into()) {
+///     Err(CodecError::Version(err)) => {
+///         assert_eq!(err.expected(), 2);
+///         assert_eq!(err.actual(), Some(1));
+///         assert_eq!(
+///             err.to_string(),
+///             "schema version mismatch: expected version 2, but frame has version 1",
+///         );
+///     }
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+///
+/// [`VersionMismatch`]: struct.VersionMismatch.html
+#[pin_project]
+#[derive(Clone, Debug)]
+pub struct Versioned<C> {
+    #[pin]
+    inner: C,
+    version: u8,
+}
+
+impl<C> Versioned<C> {
+    /// Creates a new serializer and deserializer prefixing the frames of
+    /// `inner` with `version`.
+    pub fn new(inner: C, version: u8) -> Self {
+        Versioned { inner, version }
+    }
+
+    /// Returns the schema version written and expected by this codec.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns a reference to the inner serializer and deserializer.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner serializer and deserializer.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Consumes the `Versioned`, returning the inner serializer and
+    /// deserializer.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<T, C> Deserializer<T> for Versioned<C>
+where
+    C: Deserializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<T, Self::Error> {
+        let this = self.project();
+
+        match src.split_first() {
+            Some((&version, rest)) if version == *this.version => this
+                .inner
+                .deserialize(&BytesMut::from(rest))
+                .map_err(Into::into),
+            split => Err(VersionMismatch {
+                expected: *this.version,
+                actual: split.map(|(&version, _)| version),
+            }
+            .into()),
+        }
+    }
+}
+
+impl<T, C> Serializer<T> for Versioned<C>
+where
+    C: Serializer<T>,
+    C::Error: Into<CodecError>,
+{
+    type Error = CodecError;
+
+    fn serialize(self: Pin<&mut Self>, item: &T) -> Result<Bytes, Self::Error> {
+        let this = self.project();
+        let frame = this.inner.serialize(item).map_err(Into::into)?;
+
+        let mut buf = BytesMut::with_capacity(frame.len() + 1);
+        buf.put_u8(*this.version);
+        buf.extend_from_slice(&frame);
+        Ok(buf.freeze())
+    }
+}
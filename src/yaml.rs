@@ -0,0 +1,142 @@
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use tokio_serde::{FramedRead, FramedWrite};
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    format::{impl_symmetrical_codec, SymmetricalCodec},
+    CodecError,
+};
+
+/// Adapts a stream of YAML encoded buffers to a stream of values.
+pub type ReadYaml<T, U> = FramedRead<T, U, Yaml<U>>;
+
+/// Adapts a buffer sink to a value sink by encoding the values as YAML.
+pub type WriteYaml<T, U> = FramedWrite<T, U, Yaml<U>>;
+
+/// YAML serializer and deserializer backed by [serde_yaml].
+///
+/// Each frame holds a single YAML document. Frames holding a stream of
+/// several documents are read with [`deserialize_documents`].
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+///
+/// use serde::{Deserialize, Serialize};
+/// use tokio_serde::{Deserializer, Serializer};
+/// use tokio_serde_codecs::Yaml;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Config {
+///     name: String,
+///     motd: String,
+///     script: String,
+/// }
+///
+/// let config = Config {
+///     name: "web".into(),
+///     motd: "Welcome!\nMaintenance on Sunday.\n".into(),
+///     script: "set -e\n  make\nmake install".into(),
+/// };
+///
+/// let mut codec = Yaml::<Config>::new();
+///
+/// let frame = Pin::new(&mut codec).serialize(&config).unwrap();
+/// // Multiline strings are written as block scalars
+/// let text = std::str::from_utf8(&frame).unwrap();
+/// assert!(text.contains("motd: |\n  Welcome!\n  Maintenance on Sunday.\n"));
+///
+/// assert_eq!(Pin::new(&mut codec).deserialize(&frame.into()).unwrap(), config);
+/// ```
+///
+/// [`deserialize_documents`]: #method.deserialize_documents
+/// [serde_yaml]: https://docs.rs/serde_yaml
+pub struct Yaml<T> {
+    ghost: PhantomData<T>,
+}
+
+impl<T> Yaml<T> {
+    /// Creates a new YAML serializer and deserializer.
+    pub fn new() -> Self {
+        Yaml { ghost: PhantomData }
+    }
+
+    /// Deserializes every document of a frame holding a YAML stream of
+    /// several documents.
+    ///
+    /// The documents are separated by `---` lines, and the stream may end
+    /// with a `...` line, as in a file of YAML documents. This is the YAML
+    /// counterpart of [`Json::deserialize_many`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use tokio_serde_codecs::Yaml;
+    ///
+    /// let frame = BytesMut::from(&b"---\nname: web\nport: 80\n---\nname: db\nport: 5432\n"[..]);
+    ///
+    /// let documents = Yaml::<serde_yaml::Value>::new()
+    ///     .deserialize_documents(&frame)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(documents.len(), 2);
+    /// assert_eq!(documents[1]["name"], "db");
+    /// assert_eq!(documents[1]["port"], 5432);
+    /// ```
+    ///
+    /// [`Json::deserialize_many`]: struct.Json.html#method.deserialize_many
+    pub fn deserialize_documents<'a>(
+        &self,
+        src: &'a BytesMut,
+    ) -> impl Iterator<Item = Result<T, CodecError>> + 'a
+    where
+        for<'de> T: Deserialize<'de> + 'a,
+    {
+        serde_yaml::Deserializer::from_slice(src)
+            .map(|document| T::deserialize(document).map_err(Into::into))
+    }
+}
+
+impl<T> Default for Yaml<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Yaml<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Yaml<T> {}
+
+impl<T> fmt::Debug for Yaml<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Yaml").finish()
+    }
+}
+
+impl<T> SymmetricalCodec<T> for Yaml<T> {
+    const NAME: &'static str = "yaml";
+
+    fn encode<U>(&self, item: &U) -> Result<Bytes, CodecError>
+    where
+        U: Serialize,
+    {
+        Ok(serde_yaml::to_string(item)?.into())
+    }
+
+    fn decode(&self, src: &[u8]) -> Result<T, CodecError>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        Ok(serde_yaml::from_slice(src)?)
+    }
+}
+
+impl_symmetrical_codec!(Yaml);
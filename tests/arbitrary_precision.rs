@@ -0,0 +1,69 @@
+//! Checks that numbers keep all of their digits with the
+//! `arbitrary-precision` feature.
+//!
+//! Run with `cargo test --features arbitrary-precision --test arbitrary_precision`,
+//! and with `--features arbitrary-precision,cbor` to check the numbers written
+//! to other formats by a `DynCodec`.
+
+#![cfg(feature = "arbitrary-precision")]
+
+use std::pin::Pin;
+
+use bytes::BytesMut;
+use serde_json::{json, Value};
+use tokio_serde::{Deserializer, Serializer};
+#[cfg(feature = "cbor")]
+use tokio_serde_codecs::Cbor;
+use tokio_serde_codecs::{DynCodec, Json};
+
+fn round_trip(frame: &str) -> String {
+    let mut json = Json::<Value>::new();
+    let value = Pin::new(&mut json)
+        .deserialize(&BytesMut::from(frame.as_bytes()))
+        .unwrap();
+    let frame = Pin::new(&mut json).serialize(&value).unwrap();
+    String::from_utf8(frame.to_vec()).unwrap()
+}
+
+#[test]
+fn big_integer_round_trips_exactly() {
+    let frame = "1234567890123456789012345678901234567890";
+    assert_eq!(frame.len(), 40);
+    assert_eq!(round_trip(frame), frame);
+
+    let frame = "[-1234567890123456789012345678901234567890]";
+    assert_eq!(round_trip(frame), frame);
+}
+
+#[test]
+fn large_float_round_trips_exactly() {
+    // serde_json writes the sign of the exponent, but keeps every digit
+    assert_eq!(round_trip("1e308"), "1e+308");
+    assert_eq!(round_trip("1e+308").parse::<f64>().unwrap(), 1e308);
+
+    // More digits than an `f64` holds
+    let frame = "1.0000000000000000000000000000000000001e+308";
+    assert_eq!(round_trip(frame), frame);
+}
+
+#[test]
+fn dyn_codec_keeps_digits() {
+    let codec: Box<dyn DynCodec> = Box::new(Json::new());
+    let frame = "[1234567890123456789012345678901234567890,0.1]";
+    let value = codec.decode(frame.as_bytes()).unwrap();
+    assert_eq!(codec.encode(&value).unwrap(), frame.as_bytes());
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn dyn_codec_writes_plain_numbers_to_cbor() {
+    let codec: Box<dyn DynCodec> = Box::new(Cbor::new());
+
+    let value = json!([1, -2, 0.5]);
+    let frame = codec.encode(&value).unwrap();
+    assert_eq!(frame, &[0x83, 0x01, 0x21, 0xF9, 0x38, 0x00][..]);
+    assert_eq!(codec.decode(&frame).unwrap(), value);
+
+    let value: Value = serde_json::from_str("[1e400]").unwrap();
+    assert!(codec.encode(&value).is_err());
+}
@@ -0,0 +1,81 @@
+//! Checks that formats can be used on their own, without the JSON support
+//! enabled by default, and that only the types of the enabled formats are
+//! exported.
+//!
+//! Run with e.g. `cargo test --no-default-features --features cbor --tests`,
+//! as the doc tests of the crate use JSON.
+
+/// Defines a `Json` type, which is ambiguous with the one of this crate
+/// when glob imported alongside it, so that using it fails to compile if
+/// the crate exports `Json` as well.
+#[cfg(not(feature = "json"))]
+#[allow(dead_code)]
+mod shadow {
+    pub struct Json;
+}
+
+#[cfg(all(feature = "cbor", not(feature = "json")))]
+mod cbor_only {
+    use std::pin::Pin;
+
+    use tokio_serde::{Deserializer, Serializer};
+
+    use crate::shadow::*;
+    use tokio_serde_codecs::*;
+
+    #[test]
+    fn exports_cbor_but_not_json() {
+        let _: Option<Json> = None;
+
+        let mut codec = Cbor::<Vec<u32>>::new();
+        let frame = Pin::new(&mut codec).serialize(&vec![1, 2, 3]).unwrap();
+        let value = Pin::new(&mut codec).deserialize(&frame.into()).unwrap();
+        assert_eq!(value, [1, 2, 3]);
+    }
+}
+
+#[cfg(all(feature = "messagepack", not(feature = "json")))]
+mod messagepack_only {
+    use std::pin::Pin;
+
+    use tokio_serde::{Deserializer, Serializer};
+
+    use crate::shadow::*;
+    use tokio_serde_codecs::*;
+
+    #[test]
+    fn exports_messagepack_but_not_json() {
+        let _: Option<Json> = None;
+
+        let mut codec = MessagePack::<(String, bool)>::new();
+        let value = ("on".to_owned(), true);
+        let frame = Pin::new(&mut codec).serialize(&value).unwrap();
+        assert_eq!(
+            Pin::new(&mut codec).deserialize(&frame.into()).unwrap(),
+            value
+        );
+    }
+}
+
+#[cfg(all(feature = "yaml", not(feature = "json")))]
+mod yaml_only {
+    use std::pin::Pin;
+
+    use tokio_serde::{Deserializer, Serializer};
+
+    use crate::shadow::*;
+    use tokio_serde_codecs::*;
+
+    #[test]
+    fn exports_yaml_but_not_json() {
+        let _: Option<Json> = None;
+
+        let mut codec = Yaml::<Vec<String>>::new();
+        let value = vec!["a".to_owned(), "b".to_owned()];
+        let frame = Pin::new(&mut codec).serialize(&value).unwrap();
+        assert_eq!(
+            Pin::new(&mut codec).deserialize(&frame.into()).unwrap(),
+            value
+        );
+    }
+}
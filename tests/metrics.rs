@@ -0,0 +1,85 @@
+//! Checks the counters recorded with the `metrics` feature, using a recorder
+//! which keeps them in memory.
+//!
+//! Run with `cargo test --features metrics --test metrics`.
+
+#![cfg(feature = "metrics")]
+
+use std::{
+    collections::BTreeMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use bytes::BytesMut;
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use tokio_serde::{Deserializer, Serializer};
+use tokio_serde_codecs::Json;
+
+/// Recorder keeping the counters by name and labels, e.g.
+/// `frames_encoded{codec=json}`.
+#[derive(Default)]
+struct Counters(Mutex<BTreeMap<String, Arc<AtomicU64>>>);
+
+impl Counters {
+    fn get(&self, key: &str) -> u64 {
+        self.0
+            .lock()
+            .unwrap()
+            .get(key)
+            .map_or(0, |counter| counter.load(Ordering::Relaxed))
+    }
+}
+
+impl Recorder for Counters {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let labels: Vec<_> = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect();
+        let name = format!("{}{{{}}}", key.name(), labels.join(","));
+
+        let counter = self.0.lock().unwrap().entry(name).or_default().clone();
+        Counter::from_arc(counter)
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[test]
+fn counters_per_codec() {
+    let counters = Counters::default();
+
+    metrics::with_local_recorder(&counters, || {
+        let mut json = Json::<Vec<u32>>::new();
+        for _ in 0..2 {
+            let frame = Pin::new(&mut json).serialize(&vec![1, 2, 3]).unwrap();
+            Pin::new(&mut json).deserialize(&frame.into()).unwrap();
+        }
+
+        // Frames which fail to decode aren't counted
+        Pin::new(&mut json)
+            .deserialize(&BytesMut::from(&b"[1,"[..]))
+            .unwrap_err();
+    });
+
+    assert_eq!(counters.get("frames_encoded{codec=json}"), 2);
+    assert_eq!(counters.get("bytes_encoded{codec=json}"), 14);
+    assert_eq!(counters.get("frames_decoded{codec=json}"), 2);
+    assert_eq!(counters.get("bytes_decoded{codec=json}"), 14);
+}
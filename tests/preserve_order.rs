@@ -0,0 +1,34 @@
+//! Checks that objects keep the order of their members with the
+//! `preserve-order` feature.
+//!
+//! Run with `cargo test --features preserve-order --test preserve_order`.
+
+#![cfg(feature = "preserve-order")]
+
+use std::pin::Pin;
+
+use bytes::BytesMut;
+use serde_json::Value;
+use tokio_serde::{Deserializer, Serializer};
+use tokio_serde_codecs::Json;
+
+#[test]
+fn key_order_round_trips() {
+    let mut json = Json::<Value>::new();
+
+    let frame = BytesMut::from(&br#"{"z":1,"a":2}"#[..]);
+    let value = Pin::new(&mut json).deserialize(&frame).unwrap();
+    let keys: Vec<_> = value.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["z", "a"]);
+
+    assert_eq!(Pin::new(&mut json).serialize(&value).unwrap(), frame);
+}
+
+#[test]
+fn nested_key_order_round_trips() {
+    let mut json = Json::<Value>::new();
+
+    let frame = BytesMut::from(&br#"{"z":{"y":[{"b":1,"a":2}],"x":null},"a":2}"#[..]);
+    let value = Pin::new(&mut json).deserialize(&frame).unwrap();
+    assert_eq!(Pin::new(&mut json).serialize(&value).unwrap(), frame);
+}
@@ -0,0 +1,83 @@
+//! Checks `RawValue`s with either parser, as the `simd` feature may be
+//! enabled by another crate in the dependency graph.
+//!
+//! Run with `cargo test --features raw-value --test raw_value`, and again
+//! with `--features raw-value,simd`.
+
+#![cfg(feature = "raw-value")]
+
+use std::pin::Pin;
+
+use bytes::BytesMut;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use tokio_serde::{Deserializer, Serializer};
+use tokio_serde_codecs::{CodecError, Json, NumberPolicy, RawJson};
+
+#[derive(Serialize, Deserialize)]
+struct Message {
+    id: u32,
+    payload: Box<RawValue>,
+}
+
+#[test]
+fn raw_json_round_trip() {
+    let frame = BytesMut::from(&b"{ \"a\" :  [1,\n  2.50] }"[..]);
+
+    let mut json = RawJson::new();
+    let value = Pin::new(&mut json).deserialize(&frame).unwrap();
+    assert_eq!(value.get(), "{ \"a\" :  [1,\n  2.50] }");
+    assert_eq!(Pin::new(&mut json).serialize(&value).unwrap(), frame);
+}
+
+#[test]
+fn raw_value_field() {
+    let frame = BytesMut::from(&br#"{"id":7,"payload":{ "readings" : [1.50, 2] }}"#[..]);
+
+    let mut json = Json::<Message>::new();
+    let message = Pin::new(&mut json).deserialize(&frame).unwrap();
+    assert_eq!(message.id, 7);
+    assert_eq!(message.payload.get(), r#"{ "readings" : [1.50, 2] }"#);
+    assert_eq!(Pin::new(&mut json).serialize(&message).unwrap(), frame);
+}
+
+#[test]
+fn invalid_frames_fail_like_serde_json() {
+    for frame in &["", "{\"id\":7,\"payload\":[1,}", "{\"id\":7} [1]"] {
+        let expected = serde_json::from_str::<Message>(frame)
+            .err()
+            .unwrap()
+            .to_string();
+
+        match Pin::new(&mut Json::<Message>::new()).deserialize(&BytesMut::from(frame.as_bytes())) {
+            Err(CodecError::Json(err)) => assert_eq!(err.get_ref().to_string(), expected),
+            Err(err) => panic!("unexpected error for {:?}: {:?}", frame, err),
+            Ok(_) => panic!("{:?} was accepted", frame),
+        }
+    }
+}
+
+#[test]
+fn raw_text_is_not_a_string() {
+    let mut json = Json::<Message>::new().max_string_len(8);
+
+    let frame = BytesMut::from(&br#"{"id":7,"payload":["short","words"]}"#[..]);
+    let message = Pin::new(&mut json).deserialize(&frame).unwrap();
+    assert_eq!(message.payload.get(), r#"["short","words"]"#);
+
+    let mut json = Json::<(String, Box<RawValue>)>::new().max_string_len(8);
+    let frame = BytesMut::from(&br#"["a long string",1]"#[..]);
+    let err = Pin::new(&mut json).deserialize(&frame).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("string exceeds maximum length of 8 bytes"));
+}
+
+#[test]
+fn literal_numbers_in_raw_values() {
+    let mut json = Json::<Message>::new().number_policy(NumberPolicy::Literal);
+
+    let frame = BytesMut::from(&br#"{"id":7,"payload":[NaN,-Infinity,1e400]}"#[..]);
+    let message = Pin::new(&mut json).deserialize(&frame).unwrap();
+    assert_eq!(message.payload.get(), "[NaN,-Infinity,1e400]");
+}
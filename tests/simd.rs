@@ -0,0 +1,90 @@
+//! Checks that the simd-json based deserializer reads the same values as
+//! serde_json.
+//!
+//! Run with `cargo test --features simd --test simd`.
+
+#![cfg(feature = "simd")]
+
+use std::pin::Pin;
+
+use bytes::BytesMut;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio_serde::Deserializer;
+use tokio_serde_codecs::{CodecError, Json};
+
+const FRAMES: &[&str] = &[
+    "null",
+    "true",
+    "18446744073709551615",
+    "-9223372036854775808",
+    "1.5e-7",
+    "[]",
+    "{}",
+    r#""escapes \" \\ \/ \b \f \n \r \t é 😀""#,
+    r#"{"a":[1,{"b":[2.25,null,"c"]}],"d":{"e":{"f":[]}}}"#,
+    "  [ 1 ,\n 2 ]\t",
+];
+
+#[test]
+fn values_match_serde_json() {
+    for frame in FRAMES {
+        let expected: Value = serde_json::from_str(frame).unwrap();
+        let value = Pin::new(&mut Json::<Value>::new())
+            .deserialize(&BytesMut::from(frame.as_bytes()))
+            .unwrap();
+        assert_eq!(value, expected, "{}", frame);
+    }
+}
+
+// With `arbitrary-precision`, serde_json parses every frame
+#[cfg(not(feature = "arbitrary-precision"))]
+#[test]
+fn negative_zero_is_an_integer() {
+    // The one known difference, documented with the feature
+    let expected: Value = serde_json::from_str("-0").unwrap();
+    assert!(expected.is_f64());
+
+    let value = Pin::new(&mut Json::<Value>::new())
+        .deserialize(&BytesMut::from(&b"-0"[..]))
+        .unwrap();
+    assert_eq!(value.as_u64(), Some(0));
+}
+
+#[test]
+fn structs_match_serde_json() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Event {
+        id: u64,
+        name: String,
+        tags: Vec<String>,
+        score: Option<f64>,
+    }
+
+    let frame = r#"{"id":7,"name":"deploy","tags":["a","b"],"score":null}"#;
+    let expected: Event = serde_json::from_str(frame).unwrap();
+    let event = Pin::new(&mut Json::<Event>::new())
+        .deserialize(&BytesMut::from(frame.as_bytes()))
+        .unwrap();
+    assert_eq!(event, expected);
+}
+
+#[test]
+fn invalid_frames_fail_like_serde_json() {
+    for frame in &["", "[1,", "{\"a\" 1}", "nul", "[1] [2]"] {
+        let expected = serde_json::from_str::<Value>(frame).unwrap_err();
+
+        // The error, including its position, is the one of serde_json
+        match Pin::new(&mut Json::<Value>::new()).deserialize(&BytesMut::from(frame.as_bytes())) {
+            Err(CodecError::Json(err)) => {
+                assert_eq!(
+                    err.get_ref().to_string(),
+                    expected.to_string(),
+                    "{:?}",
+                    frame
+                );
+            }
+            other => panic!("unexpected result for {:?}: {:?}", frame, other),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Checks the spans and events emitted with the `tracing` feature, using a
+//! subscriber which records them.
+//!
+//! Run with `cargo test --features tracing --test tracing`.
+
+#![cfg(feature = "tracing")]
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use bytes::BytesMut;
+use futures::{executor::block_on, stream, TryStreamExt};
+use tokio_serde::{Deserializer, Serializer};
+use tokio_serde_codecs::{ErrorPolicy, Json, Recovering};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Fields of a span or event, formatted as strings.
+type Fields = BTreeMap<&'static str, String>;
+
+struct Recorded {
+    name: &'static str,
+    fields: Fields,
+}
+
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<Recorded>>>,
+    events: Arc<Mutex<Vec<Fields>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let mut fields = Fields::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(Recorded {
+            name: attrs.metadata().name(),
+            fields,
+        });
+        span::Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, id: &span::Id, values: &span::Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let span = &mut spans[id.into_u64() as usize - 1];
+        values.record(&mut FieldVisitor(&mut span.fields));
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn spans_around_frames() {
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut json = Json::<Vec<u32>>::new();
+        let frame = Pin::new(&mut json).serialize(&vec![1, 2, 3]).unwrap();
+        Pin::new(&mut json).deserialize(&frame.into()).unwrap();
+        Pin::new(&mut json)
+            .deserialize(&BytesMut::from(&b"[1,"[..]))
+            .unwrap_err();
+    });
+
+    let spans = recorder.spans.lock().unwrap();
+    let summary: Vec<_> = spans.iter().map(|span| span.name).collect();
+    assert_eq!(summary, ["serialize", "deserialize", "deserialize"]);
+
+    assert_eq!(spans[0].fields["codec"], "json");
+    assert_eq!(spans[0].fields["len"], "7");
+    assert!(!spans[0].fields.contains_key("error"));
+
+    assert_eq!(spans[1].fields["codec"], "json");
+    assert_eq!(spans[1].fields["len"], "7");
+    assert!(!spans[1].fields.contains_key("error"));
+
+    assert_eq!(spans[2].fields["len"], "3");
+    let error = &spans[2].fields["error"];
+    assert!(error.contains("EOF while parsing a value"), "{}", error);
+}
+
+#[test]
+fn event_for_skipped_frame() {
+    let recorder = Recorder::default();
+
+    let values = tracing::subscriber::with_default(recorder.clone(), || {
+        let frames = stream::iter(vec![
+            Ok::<_, std::io::Error>(BytesMut::from(&b"1"[..])),
+            Ok(BytesMut::from(&b"{oops"[..])),
+        ]);
+        let values = Recovering::new(frames, Json::<i32>::new()).on_error(ErrorPolicy::Skip);
+        block_on(values.try_collect::<Vec<_>>()).unwrap()
+    });
+    assert_eq!(values, [1]);
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0]["message"],
+        "skipping frame which failed to deserialize"
+    );
+    assert_eq!(events[0]["len"], "5");
+    let error = &events[0]["error"];
+    assert!(error.contains("in frame of 5 bytes"), "{}", error);
+}
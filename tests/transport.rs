@@ -0,0 +1,74 @@
+//! Exchanges values between two peers connected by a socket pair, checking
+//! the codecs against real transports rather than frames built by hand.
+
+#![cfg(all(unix, feature = "json"))]
+
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    codec::{Framed, LengthDelimitedCodec},
+    net::UnixStream,
+};
+use tokio_serde::{FramedRead, FramedWrite};
+use tokio_serde_codecs::Json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Request {
+    id: u32,
+    query: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Response {
+    id: u32,
+    rows: Vec<String>,
+}
+
+#[tokio::test]
+async fn distinct_request_and_response_types() {
+    let (client, server) = UnixStream::pair().unwrap();
+
+    // The client writes requests and reads responses
+    let client = Framed::new(client, LengthDelimitedCodec::new());
+    let mut client = FramedWrite::new(
+        FramedRead::new(client, Json::<Response, Request>::new()),
+        Json::<Response, Request>::new(),
+    );
+
+    // The server the other way around
+    let server = Framed::new(server, LengthDelimitedCodec::new());
+    let mut server = FramedWrite::new(
+        FramedRead::new(server, Json::<Request, Response>::new()),
+        Json::<Request, Response>::new(),
+    );
+
+    let request = Request {
+        id: 1,
+        query: "users".into(),
+    };
+    client.send(request).await.unwrap();
+
+    let request = server.try_next().await.unwrap().unwrap();
+    assert_eq!(
+        request,
+        Request {
+            id: 1,
+            query: "users".into(),
+        }
+    );
+
+    let response = Response {
+        id: request.id,
+        rows: vec!["jane".into(), "john".into()],
+    };
+    server.send(response).await.unwrap();
+
+    let response = client.try_next().await.unwrap().unwrap();
+    assert_eq!(
+        response,
+        Response {
+            id: 1,
+            rows: vec!["jane".into(), "john".into()],
+        }
+    );
+}